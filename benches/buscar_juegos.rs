@@ -0,0 +1,76 @@
+use chinchoncito::chinchon::{formar_juegos, formar_juegos_exhaustivo, Carta, Palo, ReglasPartida};
+use rand::{prelude::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::{
+    ops::RangeInclusive,
+    time::{Duration, Instant},
+};
+
+const MANOS: usize = 2000;
+
+fn main() {
+    let con_comodines = ReglasPartida {
+        comodines: true,
+        ..Default::default()
+    };
+    for (nombre, reglas, numeros) in [
+        ("sin comodines", ReglasPartida::default(), 1..=12),
+        ("con comodines", con_comodines, 1..=12),
+        ("muchos juegos", con_comodines, 3..=6),
+    ] {
+        let manos = manos_aleatorias(&reglas, numeros);
+        medir_y_comparar(nombre, &reglas, &manos);
+    }
+    let peor_caso: Vec<Carta> = [Palo::Copa, Palo::Espada]
+        .into_iter()
+        .flat_map(|palo| (3..=5).map(move |num| Carta { num, palo }))
+        .chain((1..=2).map(|num| Carta {
+            num,
+            palo: Palo::Comodin,
+        }))
+        .collect();
+    medir_y_comparar("peor caso", &con_comodines, &vec![peor_caso; MANOS]);
+}
+
+fn medir_y_comparar(nombre: &str, reglas: &ReglasPartida, manos: &[Vec<Carta>]) {
+    let (tiempo_rapido, resultados_rapido) = medir(manos, |m| formar_juegos(m, reglas));
+    let (tiempo_exhaustivo, resultados_exhaustivo) =
+        medir(manos, |m| formar_juegos_exhaustivo(m, reglas));
+    assert_eq!(resultados_rapido, resultados_exhaustivo);
+    println!(
+        "{nombre}: {MANOS} manos, memoizado {:?} ({:?}/mano), exhaustivo {:?} ({:?}/mano)",
+        tiempo_rapido,
+        tiempo_rapido / MANOS as u32,
+        tiempo_exhaustivo,
+        tiempo_exhaustivo / MANOS as u32,
+    );
+}
+
+fn manos_aleatorias(reglas: &ReglasPartida, numeros: RangeInclusive<u8>) -> Vec<Vec<Carta>> {
+    let mut mazo: Vec<Carta> = numeros
+        .flat_map(|num| {
+            [Palo::Copa, Palo::Espada, Palo::Oro, Palo::Basto]
+                .into_iter()
+                .map(move |palo| Carta { num, palo })
+        })
+        .collect();
+    if reglas.comodines {
+        mazo.extend((1..=2).map(|num| Carta {
+            num,
+            palo: Palo::Comodin,
+        }));
+    }
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    (0..MANOS)
+        .map(|_| {
+            mazo.shuffle(&mut rng);
+            mazo[..8].to_vec()
+        })
+        .collect()
+}
+
+fn medir<R>(manos: &[Vec<Carta>], formar: impl Fn(Vec<Carta>) -> R) -> (Duration, Vec<R>) {
+    let inicio = Instant::now();
+    let resultados = manos.iter().map(|m| formar(m.clone())).collect();
+    (inicio.elapsed(), resultados)
+}
@@ -0,0 +1,330 @@
+use anyhow::{anyhow, Result};
+use chinchoncito::chinchon::{
+    jugar_turno_bot, Dificultad, JugadaBot, Jugador, Partida, PilaCartas, PuntajeParejas,
+    ReglasPartida, TipoMazo,
+};
+use rand::{prelude::SliceRandom, thread_rng, Rng};
+use serenity::model::id::UserId;
+use std::{env, fs, time::Instant};
+
+const MAX_TURNOS: usize = 10_000;
+
+const USO: &str = "\
+Uso: chinchon-sim [opciones] <estrategia> <estrategia> [<estrategia> <estrategia>]
+       chinchon-sim --repetir <archivo>
+
+Estrategias: facil, dificil, aleatoria
+
+Opciones:
+    --partidas <n>     cantidad de partidas a simular (1000)
+    --semilla <n>      semilla de la primera partida (aleatoria)
+    --limite <n>       puntos con los que se pierde (100)
+    --corta-con <n>    puntos maximos para cortar (5)
+    --comodines <n>    juega con comodines que valen n puntos
+    --cartas <40|48>   tamaño del mazo (48)
+    --reenganche       permite reengancharse una vez
+    --acomodar         permite acomodar cartas en los juegos del que corta
+    --parejas <suma|maximo>
+                       juegan 1 y 3 contra 2 y 4, sumando o tomando el maximo
+                       de los puntos de cada pareja
+    --exportar <archivo>
+                       guarda la ultima partida simulada en notacion de texto
+    --repetir <archivo>
+                       reproduce una partida exportada y muestra como termino";
+
+trait Estrategia {
+    fn nombre(&self) -> String;
+
+    fn jugar_turno(&mut self, jugador: &mut Jugador<'_>) -> JugadaBot;
+
+    fn reengancharse(&mut self) -> bool {
+        true
+    }
+}
+
+impl Estrategia for Dificultad {
+    fn nombre(&self) -> String {
+        self.nombre().to_owned()
+    }
+
+    fn jugar_turno(&mut self, jugador: &mut Jugador<'_>) -> JugadaBot {
+        jugar_turno_bot(jugador, *self)
+    }
+}
+
+struct Aleatoria;
+
+impl Estrategia for Aleatoria {
+    fn nombre(&self) -> String {
+        "aleatoria".to_owned()
+    }
+
+    fn jugar_turno(&mut self, jugador: &mut Jugador<'_>) -> JugadaBot {
+        let mut levanto = None;
+        if jugador.get_cartas().len() < 8 {
+            let pila = if thread_rng().gen_bool(0.5) {
+                PilaCartas::Descartes
+            } else {
+                PilaCartas::Mazo
+            };
+            let pila = match jugador.levantar(pila) {
+                Ok(_) => pila,
+                Err(_) => {
+                    jugador.levantar(PilaCartas::Mazo).unwrap();
+                    PilaCartas::Mazo
+                }
+            };
+            levanto = Some(pila);
+        }
+        let carta = *jugador.get_cartas().choose(&mut thread_rng()).unwrap();
+        let corte = if jugador.puede_cortar(carta) {
+            jugador.cortar(Some(carta)).ok()
+        } else {
+            None
+        };
+        if corte.is_none() {
+            jugador.tirar(carta).unwrap();
+        }
+        JugadaBot {
+            levanto,
+            carta,
+            corte,
+        }
+    }
+
+    fn reengancharse(&mut self) -> bool {
+        thread_rng().gen_bool(0.5)
+    }
+}
+
+fn parsear_estrategia(nombre: &str) -> Result<Box<dyn Estrategia>> {
+    match nombre {
+        "facil" => Ok(Box::new(Dificultad::Facil)),
+        "dificil" => Ok(Box::new(Dificultad::Dificil)),
+        "aleatoria" => Ok(Box::new(Aleatoria)),
+        _ => Err(anyhow!("Estrategia desconocida: {}", nombre)),
+    }
+}
+
+#[derive(Default)]
+struct EstadisticasEstrategia {
+    partidas: u32,
+    victorias: u32,
+    cortes: u32,
+    chinchones: u32,
+    cortes_menos_diez: u32,
+    puntos_cortes: i64,
+}
+
+#[derive(Default)]
+struct Resumen {
+    partidas: u32,
+    sin_terminar: u32,
+    rondas: u64,
+    chinchones: u32,
+}
+
+fn jugar_partida(
+    estrategias: &mut [Box<dyn Estrategia>],
+    asientos: &[usize],
+    reglas: ReglasPartida,
+    semilla: u64,
+    stats: &mut [EstadisticasEstrategia],
+    resumen: &mut Resumen,
+) -> Partida {
+    let ids: Vec<_> = (1..=asientos.len() as u64).map(UserId).collect();
+    let mut partida = Partida::empezar_con_semilla(&ids, reglas, semilla);
+    let estrategia_de = |id: UserId| asientos[(id.0 - 1) as usize];
+    for &estrategia in asientos {
+        stats[estrategia].partidas += 1;
+    }
+    resumen.partidas += 1;
+    for _ in 0..MAX_TURNOS {
+        if let Some(ganador) = partida.ganador() {
+            stats[estrategia_de(ganador)].victorias += 1;
+            if let Some(pareja) = partida.get_pareja(ganador) {
+                stats[estrategia_de(pareja)].victorias += 1;
+            }
+            return partida;
+        }
+        let turno = partida.get_turno();
+        let estrategia = estrategia_de(turno);
+        let mut jugador = partida.jugador(turno).unwrap();
+        let resultados = match estrategias[estrategia].jugar_turno(&mut jugador).corte {
+            Some(resultados) => resultados,
+            None => continue,
+        };
+        resumen.rondas += 1;
+        let propio = resultados.iter().find(|r| r.jugador == turno).unwrap();
+        let stats_corte = &mut stats[estrategia];
+        stats_corte.cortes += 1;
+        stats_corte.puntos_cortes += propio.puntos_sumados as i64;
+        if propio.chinchon {
+            stats_corte.chinchones += 1;
+            resumen.chinchones += 1;
+        } else if propio.puntos_sumados == reglas.puntos_cortar_cero {
+            stats_corte.cortes_menos_diez += 1;
+        }
+        for resultado in resultados.iter().filter(|r| r.puede_reengancharse) {
+            if estrategias[estrategia_de(resultado.jugador)].reengancharse() {
+                partida.reenganchar(resultado.jugador).unwrap();
+            }
+        }
+    }
+    resumen.sin_terminar += 1;
+    partida
+}
+
+fn repetir(archivo: &str) -> Result<()> {
+    let partida = Partida::desde_notacion(&fs::read_to_string(archivo)?)?;
+    println!(
+        "{} jugadores, semilla {}, {} acciones",
+        partida.get_jugadores().len(),
+        partida.get_semilla(),
+        partida.get_historial().len()
+    );
+    for (i, ronda) in partida.get_rondas().iter().enumerate() {
+        let puntos: Vec<_> = ronda
+            .puntos
+            .iter()
+            .map(|(jugador, sumados, total)| format!("{}: {:+} = {}", jugador, sumados, total))
+            .collect();
+        println!(
+            "Ronda {}: corta {}{} | {}",
+            i + 1,
+            ronda.corto,
+            if ronda.chinchon { " (chinchon)" } else { "" },
+            puntos.join(", ")
+        );
+    }
+    match partida.ganador() {
+        Some(ganador) => println!("Gana {}", ganador),
+        None => println!("La partida no termino, le toca a {}", partida.get_turno()),
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let mut cant_partidas = 1000;
+    let mut semilla: u64 = thread_rng().gen();
+    let mut reglas = ReglasPartida::default();
+    let mut estrategias = vec![];
+    let mut exportar = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut valor = |nombre: &str| {
+            args.next()
+                .ok_or_else(|| anyhow!("Falta el valor de {}\n\n{}", nombre, USO))
+        };
+        match arg.as_str() {
+            "--partidas" => cant_partidas = valor(&arg)?.parse()?,
+            "--semilla" => semilla = valor(&arg)?.parse()?,
+            "--limite" => reglas.limite_puntos = valor(&arg)?.parse()?,
+            "--corta-con" => reglas.max_puntos_cortar = valor(&arg)?.parse()?,
+            "--comodines" => {
+                reglas.comodines = true;
+                reglas.puntos_comodin = valor(&arg)?.parse()?;
+            }
+            "--cartas" => {
+                reglas.mazo = match valor(&arg)?.as_str() {
+                    "40" => TipoMazo::De40,
+                    "48" => TipoMazo::De48,
+                    otro => return Err(anyhow!("No hay mazos de {} cartas", otro)),
+                }
+            }
+            "--reenganche" => reglas.max_reenganches = 1,
+            "--acomodar" => reglas.acomodar = true,
+            "--parejas" => {
+                reglas.parejas = true;
+                reglas.puntaje_parejas = match valor(&arg)?.as_str() {
+                    "suma" => PuntajeParejas::Suma,
+                    "maximo" => PuntajeParejas::Maximo,
+                    otro => return Err(anyhow!("No se como juntar los puntos con {}", otro)),
+                }
+            }
+            "--exportar" => exportar = Some(valor(&arg)?),
+            "--repetir" => return repetir(&valor(&arg)?),
+            "-h" | "--help" => {
+                println!("{}", USO);
+                return Ok(());
+            }
+            nombre => estrategias.push(parsear_estrategia(nombre)?),
+        }
+    }
+    if !(2..=4).contains(&estrategias.len()) {
+        return Err(anyhow!("Se juega de 2 a 4 jugadores\n\n{}", USO));
+    }
+    if reglas.parejas && estrategias.len() != 4 {
+        return Err(anyhow!("En parejas se juega de a 4\n\n{}", USO));
+    }
+    let cant_jugadores = estrategias.len();
+    let mut stats: Vec<_> = (0..cant_jugadores)
+        .map(|_| EstadisticasEstrategia::default())
+        .collect();
+    let mut resumen = Resumen::default();
+    let inicio = Instant::now();
+    let mut ultima = None;
+    for i in 0..cant_partidas {
+        let asientos: Vec<_> = (0..cant_jugadores)
+            .map(|asiento| (asiento + i as usize) % cant_jugadores)
+            .collect();
+        ultima = Some(jugar_partida(
+            &mut estrategias,
+            &asientos,
+            reglas,
+            semilla.wrapping_add(i),
+            &mut stats,
+            &mut resumen,
+        ));
+    }
+    if let (Some(archivo), Some(partida)) = (&exportar, &ultima) {
+        fs::write(archivo, partida.notacion())?;
+    }
+    println!(
+        "{} partidas en {:?} (semilla inicial {})",
+        resumen.partidas,
+        inicio.elapsed(),
+        semilla
+    );
+    println!(
+        "Rondas por partida: {:.2}",
+        resumen.rondas as f64 / resumen.partidas as f64
+    );
+    println!(
+        "Chinchones: {} ({:.2}% de las rondas)",
+        resumen.chinchones,
+        100.0 * resumen.chinchones as f64 / resumen.rondas.max(1) as f64
+    );
+    if resumen.sin_terminar > 0 {
+        println!(
+            "Partidas sin terminar tras {} turnos: {}",
+            MAX_TURNOS, resumen.sin_terminar
+        );
+    }
+    println!();
+    println!(
+        "{:<4}{:<12}{:>10}{:>10}{:>10}{:>12}{:>10}{:>16}",
+        "#",
+        "estrategia",
+        "victorias",
+        "% vict.",
+        "cortes",
+        "chinchones",
+        "cortes-10",
+        "pts. al cortar"
+    );
+    for (i, (estrategia, stats)) in estrategias.iter().zip(&stats).enumerate() {
+        println!(
+            "{:<4}{:<12}{:>10}{:>9.2}%{:>10}{:>12}{:>10}{:>16.2}",
+            i + 1,
+            estrategia.nombre(),
+            stats.victorias,
+            100.0 * stats.victorias as f64 / stats.partidas.max(1) as f64,
+            stats.cortes,
+            stats.chinchones,
+            stats.cortes_menos_diez,
+            stats.puntos_cortes as f64 / stats.cortes.max(1) as f64,
+        );
+    }
+    Ok(())
+}
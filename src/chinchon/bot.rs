@@ -0,0 +1,289 @@
+use super::{
+    buscar_juegos::formar_juegos,
+    cartas::Carta,
+    historial::Accion,
+    partida::{Jugador, PilaCartas, ReglasPartida, ResultadoFinalRonda},
+};
+use rand::{prelude::SliceRandom, thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+use serenity::model::id::UserId;
+
+pub const MAX_BOTS: u64 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Dificultad {
+    Facil,
+    Dificil,
+}
+
+impl Dificultad {
+    pub const fn nombre(&self) -> &'static str {
+        match self {
+            Self::Facil => "facil",
+            Self::Dificil => "dificil",
+        }
+    }
+}
+
+pub const fn id_bot(asiento: u64) -> UserId {
+    UserId(asiento + 1)
+}
+
+pub const fn es_bot(id: UserId) -> bool {
+    id.0 >= 1 && id.0 <= MAX_BOTS
+}
+
+pub fn nombre_bot(id: UserId) -> String {
+    format!("CPU {}", id.0)
+}
+
+#[derive(Debug, Clone)]
+pub struct JugadaBot {
+    pub levanto: Option<PilaCartas>,
+    pub carta: Carta,
+    pub corte: Option<Vec<ResultadoFinalRonda>>,
+}
+
+pub fn jugar_turno_bot(jugador: &mut Jugador<'_>, dificultad: Dificultad) -> JugadaBot {
+    let reglas = jugador.partida.get_reglas();
+    let mut levanto = None;
+    let mut levantada = None;
+    if jugador.get_cartas().len() < 8 {
+        let pila = elegir_pila(jugador, dificultad, &reglas);
+        let (pila, carta) = match jugador.levantar(pila) {
+            Ok(carta) => (pila, carta),
+            Err(_) => (
+                PilaCartas::Mazo,
+                jugador.levantar(PilaCartas::Mazo).unwrap(),
+            ),
+        };
+        levanto = Some(pila);
+        levantada = Some(carta);
+    }
+    let carta = match dificultad {
+        Dificultad::Facil => elegir_descarte_facil(jugador, &reglas),
+        Dificultad::Dificil => elegir_descarte_dificil(jugador, &reglas, levantada),
+    };
+    let corte = if jugador.puede_cortar(carta) {
+        jugador.cortar(Some(carta)).ok()
+    } else {
+        None
+    };
+    if corte.is_none() {
+        jugador.tirar(carta).unwrap();
+    }
+    JugadaBot {
+        levanto,
+        carta,
+        corte,
+    }
+}
+
+fn elegir_pila(
+    jugador: &Jugador<'_>,
+    dificultad: Dificultad,
+    reglas: &ReglasPartida,
+) -> PilaCartas {
+    let descarte = match jugador.partida.get_descarte() {
+        Some(descarte) => descarte,
+        None => return PilaCartas::Mazo,
+    };
+    let mut mano = jugador.get_cartas();
+    mano.push(descarte);
+    let (_, juegos) = formar_juegos(mano, reglas);
+    let sirve = juegos.iter().flatten().any(|c| *c == descarte);
+    let levantar_descarte = match dificultad {
+        Dificultad::Facil => thread_rng().gen_bool(if sirve { 0.5 } else { 0.1 }),
+        Dificultad::Dificil => sirve,
+    };
+    if levantar_descarte {
+        PilaCartas::Descartes
+    } else {
+        PilaCartas::Mazo
+    }
+}
+
+fn elegir_descarte_facil(jugador: &Jugador<'_>, reglas: &ReglasPartida) -> Carta {
+    let mano = jugador.get_cartas();
+    let (_, juegos) = formar_juegos(mano.clone(), reglas);
+    let sobrantes: Vec<_> = mano
+        .iter()
+        .filter(|c| juegos.iter().flatten().all(|cj| cj != *c))
+        .copied()
+        .collect();
+    if let Some(carta) = sobrantes.iter().find(|c| jugador.puede_cortar(**c)) {
+        return *carta;
+    }
+    *sobrantes
+        .choose(&mut thread_rng())
+        .or_else(|| mano.choose(&mut thread_rng()))
+        .unwrap()
+}
+
+fn elegir_descarte_dificil(
+    jugador: &Jugador<'_>,
+    reglas: &ReglasPartida,
+    levantada: Option<Carta>,
+) -> Carta {
+    let mano = jugador.get_cartas();
+    let levantadas = levantadas_por_rivales(jugador.partida.get_historial(), jugador.get_id());
+    let levantada_ahora = match jugador.partida.get_historial().last() {
+        Some(Accion::Levantar {
+            pila: PilaCartas::Descartes,
+            ..
+        }) => ultimo_descarte(jugador.partida.get_historial()),
+        _ => None,
+    };
+    let candidatas: Vec<_> = mano
+        .iter()
+        .filter(|c| Some(**c) != levantada_ahora)
+        .copied()
+        .collect();
+    if let Some(carta) = candidatas.iter().find(|c| jugador.puede_cortar(**c)) {
+        return *carta;
+    }
+    if ronda_trabada(jugador.partida.get_historial(), reglas) {
+        let (_, juegos) = formar_juegos(mano.clone(), reglas);
+        let sueltas = candidatas
+            .iter()
+            .filter(|c| Some(**c) != levantada)
+            .filter(|c| juegos.iter().flatten().all(|cj| cj != *c));
+        if let Some(carta) = sueltas.max_by_key(|c| reglas.puntos_carta(c)) {
+            return *carta;
+        }
+    }
+    candidatas
+        .iter()
+        .min_by_key(|carta| {
+            let resto: Vec<_> = mano.iter().filter(|c| c != carta).copied().collect();
+            let (puntos, _) = formar_juegos(resto, reglas);
+            let peligro = levantadas
+                .iter()
+                .filter(|l| combinan(l, carta, reglas))
+                .count();
+            let aislada = !mano
+                .iter()
+                .any(|c| c != *carta && combinan(c, carta, reglas));
+            (!aislada, puntos, peligro, -reglas.puntos_carta(carta))
+        })
+        .copied()
+        .unwrap()
+}
+
+fn ronda_trabada(historial: &[Accion], reglas: &ReglasPartida) -> bool {
+    let tiros = historial
+        .iter()
+        .rev()
+        .take_while(|a| !matches!(a, Accion::Repartir))
+        .filter(|a| matches!(a, Accion::Tirar { .. }))
+        .count();
+    tiros > reglas.mazo.numeros().len() * 4
+}
+
+fn combinan(a: &Carta, b: &Carta, reglas: &ReglasPartida) -> bool {
+    let numeros = reglas.mazo.numeros();
+    let posicion = |c: &Carta| numeros.iter().position(|n| *n == c.num);
+    a.es_comodin()
+        || b.es_comodin()
+        || a.num == b.num
+        || (a.palo == b.palo
+            && posicion(a)
+                .zip(posicion(b))
+                .is_some_and(|(pa, pb)| pa.abs_diff(pb) <= 2))
+}
+
+fn ultimo_descarte(historial: &[Accion]) -> Option<Carta> {
+    historial.iter().rev().find_map(|a| match a {
+        Accion::Tirar { carta, .. } => Some(*carta),
+        _ => None,
+    })
+}
+
+fn levantadas_por_rivales(historial: &[Accion], propio: UserId) -> Vec<Carta> {
+    let mut descartes = vec![];
+    let mut levantadas = vec![];
+    for accion in historial {
+        match accion {
+            Accion::Repartir => descartes.clear(),
+            Accion::Tirar { carta, .. } => descartes.push(*carta),
+            Accion::Levantar {
+                jugador,
+                pila: PilaCartas::Descartes,
+            } => {
+                if let Some(carta) = descartes.pop() {
+                    if *jugador != propio {
+                        levantadas.push(carta);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    levantadas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chinchon::{Palo, Partida, TipoMazo};
+
+    const MAX_TURNOS: usize = 5000;
+
+    fn termina(jugadores: u64, reglas: ReglasPartida, semilla: u64) -> bool {
+        let ids: Vec<_> = (0..jugadores).map(id_bot).collect();
+        let mut partida = Partida::empezar_con_semilla(&ids, reglas, semilla);
+        for _ in 0..MAX_TURNOS {
+            if partida.ganador().is_some() {
+                return true;
+            }
+            let turno = partida.get_turno();
+            jugar_turno_bot(&mut partida.jugador(turno).unwrap(), Dificultad::Dificil);
+            assert_eq!(partida.validar(), Ok(()), "semilla {}", semilla);
+        }
+        false
+    }
+
+    #[test]
+    fn combinan_segun_el_mazo() {
+        let siete = Carta {
+            num: 7,
+            palo: Palo::Copa,
+        };
+        let diez = Carta {
+            num: 10,
+            palo: Palo::Copa,
+        };
+        let once = Carta {
+            num: 11,
+            palo: Palo::Copa,
+        };
+        let de_40 = ReglasPartida {
+            mazo: TipoMazo::De40,
+            ..Default::default()
+        };
+        assert!(combinan(&siete, &diez, &de_40));
+        assert!(combinan(&siete, &once, &de_40));
+        assert!(!combinan(&siete, &diez, &ReglasPartida::default()));
+        assert!(!combinan(&siete, &once, &ReglasPartida::default()));
+    }
+
+    #[test]
+    fn partidas_entre_bots_dificiles_terminan() {
+        let trabadas: Vec<_> = (0..200)
+            .filter(|semilla| !termina(3, ReglasPartida::default(), *semilla))
+            .collect();
+        assert!(trabadas.is_empty(), "semillas trabadas: {:?}", trabadas);
+    }
+
+    #[test]
+    fn partidas_con_mazo_de_40_terminan() {
+        let reglas = ReglasPartida {
+            mazo: TipoMazo::De40,
+            ..Default::default()
+        };
+        let trabadas: Vec<_> = (9100..9150)
+            .filter(|semilla| !termina(4, reglas, *semilla))
+            .collect();
+        assert!(trabadas.is_empty(), "semillas trabadas: {:?}", trabadas);
+    }
+}
@@ -1,41 +1,239 @@
-use super::cartas::Carta;
+use super::{cartas::Carta, partida::ReglasPartida};
 
-pub fn formar_juegos(mut cartas: Vec<Carta>) -> (i16, Vec<Vec<Carta>>) {
+pub fn formar_juegos(cartas: Vec<Carta>, reglas: &ReglasPartida) -> (i16, Vec<Vec<Carta>>) {
+    let suma_cartas: i16 = cartas.iter().map(|c| reglas.puntos_carta(c)).sum();
+    let juegos = juegos_posibles(cartas, reglas);
+    let (puntos_juegos, mej_juegos) = mejor_combinacion_juegos(
+        &juegos.iter().map(|j| j.as_slice()).collect::<Vec<_>>(),
+        reglas,
+    );
+    (
+        suma_cartas - puntos_juegos,
+        mej_juegos.into_iter().map(|j| j.to_owned()).collect(),
+    )
+}
+
+/// La busqueda recursiva que se usaba antes, para compararla en el bench
+#[cfg(feature = "comparar-busqueda")]
+pub fn formar_juegos_exhaustivo(
+    cartas: Vec<Carta>,
+    reglas: &ReglasPartida,
+) -> (i16, Vec<Vec<Carta>>) {
+    let suma_cartas: i16 = cartas.iter().map(|c| reglas.puntos_carta(c)).sum();
+    let juegos = juegos_posibles(cartas, reglas);
+    let (puntos_juegos, mej_juegos) = mejor_combinacion_exhaustiva(
+        &juegos.iter().map(|j| j.as_slice()).collect::<Vec<_>>(),
+        reglas,
+    );
+    (
+        suma_cartas - puntos_juegos,
+        mej_juegos.into_iter().map(|j| j.to_owned()).collect(),
+    )
+}
+
+fn juegos_posibles(cartas: Vec<Carta>, reglas: &ReglasPartida) -> Vec<Vec<Carta>> {
+    let (comodines, mut cartas): (Vec<_>, Vec<_>) =
+        cartas.into_iter().partition(|c| c.es_comodin());
     let mut juegos = vec![];
     cartas.sort_unstable_by(|a, b| a.palo.cmp(&b.palo).then(a.num.cmp(&b.num)));
-    for tamaño in 3..8 {
-        for grupo in cartas.windows(tamaño) {
-            if grupo
-                .windows(2)
-                .all(|w| w[0].palo == w[1].palo && w[0].num + 1 == w[1].num)
-            {
-                juegos.push(grupo.to_owned());
+    let max_tamaño = cartas.len() + comodines.len();
+    let numeros = reglas.mazo.numeros();
+    let combinaciones_comodines: Vec<_> = subconjuntos(&comodines).collect();
+    for palo_cartas in cartas.chunk_by(|a, b| a.palo == b.palo) {
+        if palo_cartas.len() < 2 {
+            continue;
+        }
+        for inicio in 0..numeros.len() {
+            for fin in inicio + 2..numeros.len() {
+                let escalera = &numeros[inicio..=fin];
+                if escalera.len() > max_tamaño {
+                    break;
+                }
+                let cant_naturales = palo_cartas
+                    .iter()
+                    .filter(|c| escalera.contains(&c.num))
+                    .count();
+                let faltan = escalera.len() - cant_naturales;
+                if cant_naturales < 2 || faltan > comodines.len() {
+                    continue;
+                }
+                for usados in combinaciones_comodines
+                    .iter()
+                    .filter(|cs| cs.len() == faltan)
+                {
+                    let mut usados = usados.iter();
+                    let mut naturales = palo_cartas
+                        .iter()
+                        .filter(|c| escalera.contains(&c.num))
+                        .peekable();
+                    let juego = escalera
+                        .iter()
+                        .map(|&num| match naturales.next_if(|c| c.num == num) {
+                            Some(c) => *c,
+                            None => *usados.next().unwrap(),
+                        })
+                        .collect();
+                    juegos.push(juego);
+                }
             }
         }
     }
     cartas.sort_unstable_by_key(|c| c.num);
-    for grupo in cartas.windows(4) {
-        if grupo[1..].iter().all(|c| c.num == grupo[0].num) {
-            juegos.push(grupo.to_owned());
-            juegos.push(vec![grupo[0], grupo[2], grupo[3]]);
-            juegos.push(vec![grupo[0], grupo[1], grupo[3]]);
+    for mismo_num in cartas.chunk_by(|a, b| a.num == b.num) {
+        for naturales in subconjuntos(mismo_num).filter(|cs| cs.len() >= 2) {
+            for usados in &combinaciones_comodines {
+                if (3..=4).contains(&(naturales.len() + usados.len())) {
+                    juegos.push([naturales.as_slice(), usados].concat());
+                }
+            }
         }
     }
-    for grupo in cartas.windows(3) {
-        if grupo[1..].iter().all(|c| c.num == grupo[0].num) {
-            juegos.push(grupo.to_owned());
+    juegos
+}
+
+pub fn acomodar_cartas(
+    sobrantes: &mut Vec<Carta>,
+    juegos: &mut [Vec<Carta>],
+    reglas: &ReglasPartida,
+) -> Vec<(Carta, usize)> {
+    let mut acomodadas = vec![];
+    sobrantes.sort_unstable_by_key(|c| -reglas.puntos_carta(c));
+    while let Some((i_carta, i_juego)) = sobrantes.iter().enumerate().find_map(|(i, c)| {
+        juegos
+            .iter()
+            .position(|j| posicion_en_juego(*c, j, reglas).is_some())
+            .map(|j| (i, j))
+    }) {
+        let carta = sobrantes.remove(i_carta);
+        let juego = &mut juegos[i_juego];
+        let pos = posicion_en_juego(carta, juego, reglas).unwrap();
+        juego.insert(pos, carta);
+        acomodadas.push((carta, i_juego));
+    }
+    sobrantes.sort_unstable();
+    acomodadas
+}
+
+fn posicion_en_juego(carta: Carta, juego: &[Carta], reglas: &ReglasPartida) -> Option<usize> {
+    let mut naturales = juego.iter().enumerate().filter(|(_, c)| !c.es_comodin());
+    let (pos_primera, primera) = naturales.next()?;
+    let (_, segunda) = naturales.next()?;
+    if primera.num == segunda.num {
+        let lugar = juego.len() < 4;
+        return (lugar && (carta.es_comodin() || carta.num == primera.num)).then_some(juego.len());
+    }
+    let numeros = reglas.mazo.numeros();
+    let inicio = numeros.iter().position(|n| *n == primera.num)? as isize - pos_primera as isize;
+    let fin = inicio + juego.len() as isize - 1;
+    let numero = |i: isize| usize::try_from(i).ok().and_then(|i| numeros.get(i));
+    if carta.es_comodin() {
+        if numero(fin + 1).is_some() {
+            Some(juego.len())
+        } else {
+            numero(inicio - 1).map(|_| 0)
         }
+    } else if carta.palo != primera.palo {
+        None
+    } else if numero(fin + 1) == Some(&carta.num) {
+        Some(juego.len())
+    } else if numero(inicio - 1) == Some(&carta.num) {
+        Some(0)
+    } else {
+        None
     }
-    let (puntos_juegos, mej_juegos) =
-        mejor_combinacion_juegos(&juegos.iter().map(|j| j.as_slice()).collect::<Vec<_>>());
-    let suma_cartas: u8 = cartas.iter().map(|c| c.num).sum();
-    (
-        suma_cartas as i16 - puntos_juegos as i16,
-        mej_juegos.into_iter().map(|j| j.to_owned()).collect(),
-    )
 }
 
-fn mejor_combinacion_juegos<'a>(juegos_posibles: &[&'a [Carta]]) -> (i8, Vec<&'a [Carta]>) {
+fn subconjuntos(cartas: &[Carta]) -> impl Iterator<Item = Vec<Carta>> + '_ {
+    (0..1u32 << cartas.len()).map(move |mascara| {
+        cartas
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| mascara & (1 << i) != 0)
+            .map(|(_, c)| *c)
+            .collect()
+    })
+}
+
+fn mejor_combinacion_juegos<'a>(
+    juegos_posibles: &[&'a [Carta]],
+    reglas: &ReglasPartida,
+) -> (i16, Vec<&'a [Carta]>) {
+    let mut cartas: Vec<Carta> = juegos_posibles.iter().copied().flatten().copied().collect();
+    cartas.sort_unstable();
+    cartas.dedup();
+    let mascaras: Vec<u64> = juegos_posibles
+        .iter()
+        .map(|j| {
+            j.iter()
+                .map(|c| 1 << cartas.binary_search(c).unwrap())
+                .fold(0, |m, c| m | c)
+        })
+        .collect();
+    let puntajes: Vec<i16> = juegos_posibles
+        .iter()
+        .map(|j| j.iter().map(|c| reglas.puntos_carta(c)).sum())
+        .collect();
+    let mut memo = vec![None; 1 << cartas.len()];
+    let (puntaje, _) = mejor_combinacion_sin(0, &mascaras, &puntajes, &mut memo);
+    let mut juegos = vec![];
+    let mut usadas = 0;
+    while let Some(Mejor {
+        elegido: Some(i), ..
+    }) = memo[usadas as usize]
+    {
+        juegos.push(juegos_posibles[i]);
+        usadas |= mascaras[i];
+    }
+    juegos.reverse();
+    (puntaje, juegos)
+}
+
+#[derive(Clone, Copy)]
+struct Mejor {
+    puntaje: i16,
+    cant_juegos: usize,
+    elegido: Option<usize>,
+}
+
+fn mejor_combinacion_sin(
+    usadas: u64,
+    mascaras: &[u64],
+    puntajes: &[i16],
+    memo: &mut [Option<Mejor>],
+) -> (i16, usize) {
+    if let Some(mejor) = memo[usadas as usize] {
+        return (mejor.puntaje, mejor.cant_juegos);
+    }
+    let mut mejor = Mejor {
+        puntaje: 0,
+        cant_juegos: 0,
+        elegido: None,
+    };
+    for (i, mascara) in mascaras.iter().enumerate() {
+        if mascara & usadas != 0 {
+            continue;
+        }
+        let (puntaje, cant_juegos) =
+            mejor_combinacion_sin(usadas | mascara, mascaras, puntajes, memo);
+        let puntaje = puntaje + puntajes[i];
+        if puntaje > mejor.puntaje || (puntaje == mejor.puntaje && cant_juegos < mejor.cant_juegos)
+        {
+            mejor = Mejor {
+                puntaje,
+                cant_juegos: cant_juegos + 1,
+                elegido: Some(i),
+            };
+        }
+    }
+    memo[usadas as usize] = Some(mejor);
+    (mejor.puntaje, mejor.cant_juegos)
+}
+
+#[cfg(any(test, feature = "comparar-busqueda"))]
+fn mejor_combinacion_exhaustiva<'a>(
+    juegos_posibles: &[&'a [Carta]],
+    reglas: &ReglasPartida,
+) -> (i16, Vec<&'a [Carta]>) {
     let mut mej_puntaje = 0;
     let mut mej_juegos = vec![];
     for juego in juegos_posibles {
@@ -44,8 +242,8 @@ fn mejor_combinacion_juegos<'a>(juegos_posibles: &[&'a [Carta]]) -> (i8, Vec<&'a
             .filter(|j| !j.iter().any(|c| juego.contains(c)))
             .copied()
             .collect();
-        let (mut puntaje, mut juegos) = mejor_combinacion_juegos(&juegos_no_solapados);
-        puntaje += juego.iter().map(|c| c.num as i8).sum::<i8>();
+        let (mut puntaje, mut juegos) = mejor_combinacion_exhaustiva(&juegos_no_solapados, reglas);
+        puntaje += juego.iter().map(|c| reglas.puntos_carta(c)).sum::<i16>();
         if puntaje > mej_puntaje {
             mej_puntaje = puntaje;
             juegos.push(juego);
@@ -57,3 +255,210 @@ fn mejor_combinacion_juegos<'a>(juegos_posibles: &[&'a [Carta]]) -> (i8, Vec<&'a
     }
     (mej_puntaje, mej_juegos)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chinchon::{cartas::Palo, TipoMazo};
+    use rand::{prelude::SliceRandom, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::ops::RangeInclusive;
+
+    fn comparar(cartas: Vec<Carta>, reglas: &ReglasPartida) {
+        let juegos = juegos_posibles(cartas.clone(), reglas);
+        let juegos: Vec<_> = juegos.iter().map(|j| j.as_slice()).collect();
+        assert_eq!(
+            mejor_combinacion_juegos(&juegos, reglas),
+            mejor_combinacion_exhaustiva(&juegos, reglas),
+            "mano: {:?}",
+            cartas
+        );
+    }
+
+    fn comparar_manos_aleatorias(reglas: &ReglasPartida, numeros: RangeInclusive<u8>) {
+        let mut mazo: Vec<Carta> = numeros
+            .flat_map(|num| {
+                [Palo::Copa, Palo::Espada, Palo::Oro, Palo::Basto]
+                    .into_iter()
+                    .map(move |palo| Carta { num, palo })
+            })
+            .collect();
+        if reglas.comodines {
+            mazo.extend((1..=2).map(|num| Carta {
+                num,
+                palo: Palo::Comodin,
+            }));
+        }
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..300 {
+            mazo.shuffle(&mut rng);
+            comparar(mazo[..8].to_vec(), reglas);
+        }
+    }
+
+    fn con_comodines() -> ReglasPartida {
+        ReglasPartida {
+            comodines: true,
+            ..Default::default()
+        }
+    }
+
+    fn carta(num: u8, palo: Palo) -> Carta {
+        Carta { num, palo }
+    }
+
+    fn comodin() -> Carta {
+        carta(1, Palo::Comodin)
+    }
+
+    #[test]
+    fn juegos_con_comodin() {
+        let reglas = ReglasPartida {
+            comodines: true,
+            ..Default::default()
+        };
+        let escalera = vec![carta(5, Palo::Copa), comodin(), carta(7, Palo::Copa)];
+        let (puntos, juegos) = formar_juegos(escalera, &reglas);
+        assert_eq!(puntos, 0);
+        assert_eq!(juegos.len(), 1);
+        assert!(juegos[0].contains(&comodin()));
+        let iguales = vec![carta(3, Palo::Oro), carta(3, Palo::Copa), comodin()];
+        assert_eq!(formar_juegos(iguales, &reglas).0, 0);
+        let suelto = vec![carta(3, Palo::Oro), carta(9, Palo::Copa), comodin()];
+        assert_eq!(
+            formar_juegos(suelto, &reglas).0,
+            3 + 9 + reglas.puntos_comodin
+        );
+    }
+
+    #[test]
+    fn escalera_del_siete_al_diez_en_el_mazo_de_40() {
+        let mano = vec![
+            carta(6, Palo::Espada),
+            carta(7, Palo::Espada),
+            carta(10, Palo::Espada),
+        ];
+        let de_40 = ReglasPartida {
+            mazo: TipoMazo::De40,
+            ..Default::default()
+        };
+        assert_eq!(formar_juegos(mano.clone(), &de_40), (0, vec![mano.clone()]));
+        assert_eq!(formar_juegos(mano, &ReglasPartida::default()).0, 23);
+    }
+
+    #[test]
+    fn acomodar_en_ambas_puntas_de_una_escalera() {
+        let mut juegos = vec![vec![
+            carta(4, Palo::Copa),
+            carta(5, Palo::Copa),
+            carta(6, Palo::Copa),
+        ]];
+        let mut sobrantes = vec![
+            carta(3, Palo::Copa),
+            carta(7, Palo::Copa),
+            carta(9, Palo::Oro),
+        ];
+        let acomodadas = acomodar_cartas(&mut sobrantes, &mut juegos, &ReglasPartida::default());
+        assert_eq!(acomodadas.len(), 2);
+        assert_eq!(
+            juegos[0],
+            (3..=7).map(|n| carta(n, Palo::Copa)).collect::<Vec<_>>()
+        );
+        assert_eq!(sobrantes, vec![carta(9, Palo::Oro)]);
+    }
+
+    #[test]
+    fn acomodar_en_un_juego_de_iguales() {
+        let mut juegos = vec![vec![
+            carta(5, Palo::Copa),
+            carta(5, Palo::Oro),
+            carta(5, Palo::Espada),
+        ]];
+        let mut sobrantes = vec![carta(5, Palo::Basto), carta(6, Palo::Basto)];
+        let acomodadas = acomodar_cartas(&mut sobrantes, &mut juegos, &ReglasPartida::default());
+        assert_eq!(acomodadas, vec![(carta(5, Palo::Basto), 0)]);
+        assert_eq!(juegos[0].len(), 4);
+        assert_eq!(sobrantes, vec![carta(6, Palo::Basto)]);
+    }
+
+    #[test]
+    fn acomodar_en_un_juego_con_comodin() {
+        let reglas = ReglasPartida {
+            comodines: true,
+            ..Default::default()
+        };
+        let juego = [carta(4, Palo::Copa), comodin(), carta(6, Palo::Copa)];
+        assert_eq!(
+            posicion_en_juego(carta(3, Palo::Copa), &juego, &reglas),
+            Some(0)
+        );
+        assert_eq!(
+            posicion_en_juego(carta(7, Palo::Copa), &juego, &reglas),
+            Some(3)
+        );
+        assert_eq!(
+            posicion_en_juego(carta(5, Palo::Copa), &juego, &reglas),
+            None
+        );
+        let escalera_al_final = [
+            carta(10, Palo::Oro),
+            carta(11, Palo::Oro),
+            carta(12, Palo::Oro),
+        ];
+        assert_eq!(
+            posicion_en_juego(comodin(), &escalera_al_final, &reglas),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn no_se_acomoda_lo_que_no_entra() {
+        let reglas = ReglasPartida::default();
+        let mut juegos = vec![
+            vec![
+                carta(4, Palo::Copa),
+                carta(5, Palo::Copa),
+                carta(6, Palo::Copa),
+            ],
+            vec![
+                carta(2, Palo::Copa),
+                carta(2, Palo::Oro),
+                carta(2, Palo::Espada),
+                carta(2, Palo::Basto),
+            ],
+        ];
+        let originales = juegos.clone();
+        let mut sobrantes = vec![carta(8, Palo::Copa), carta(5, Palo::Oro)];
+        assert!(acomodar_cartas(&mut sobrantes, &mut juegos, &reglas).is_empty());
+        assert_eq!(juegos, originales);
+        assert_eq!(sobrantes.len(), 2);
+    }
+
+    #[test]
+    fn memoizada_igual_a_exhaustiva_sin_comodines() {
+        comparar_manos_aleatorias(&ReglasPartida::default(), 1..=12);
+    }
+
+    #[test]
+    fn memoizada_igual_a_exhaustiva_con_comodines() {
+        comparar_manos_aleatorias(&con_comodines(), 1..=12);
+    }
+
+    #[test]
+    fn memoizada_igual_a_exhaustiva_con_muchos_juegos() {
+        comparar_manos_aleatorias(&con_comodines(), 3..=6);
+    }
+
+    #[test]
+    fn memoizada_igual_a_exhaustiva_en_el_peor_caso() {
+        let mano = [Palo::Copa, Palo::Espada]
+            .into_iter()
+            .flat_map(|palo| (3..=5).map(move |num| Carta { num, palo }))
+            .chain((1..=2).map(|num| Carta {
+                num,
+                palo: Palo::Comodin,
+            }))
+            .collect();
+        comparar(mano, &con_comodines());
+    }
+}
@@ -2,10 +2,11 @@ use anyhow::{anyhow, Result};
 use once_cell::sync::{Lazy, OnceCell};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use serenity::model::id::EmojiId;
+use serenity::model::{channel::ReactionType, id::EmojiId};
 use std::{fmt::Display, str::FromStr};
 
 static EMOJIS_PALOS: OnceCell<[EmojiId; 4]> = OnceCell::new();
+const EMOJI_COMODIN: &str = "🃏";
 static COD_CARTA_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(\d{1,2}) ?(?:de? )?([a-z]+)").unwrap());
 
@@ -19,11 +20,19 @@ impl Carta {
     pub fn nombre(&self) -> String {
         format!("{} de {}", self.num, self.palo.nombre())
     }
+
+    pub fn es_comodin(&self) -> bool {
+        self.palo == Palo::Comodin
+    }
 }
 
 impl Display for Carta {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "**{}**\u{202f}{}", self.num, self.palo)
+        if self.es_comodin() {
+            write!(f, "{}", self.palo)
+        } else {
+            write!(f, "**{}**\u{202f}{}", self.num, self.palo)
+        }
     }
 }
 
@@ -55,6 +64,16 @@ pub enum CartaFromStrErr {
     NumeroInvalido(u8),
 }
 
+impl Display for CartaFromStrErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CodigoNoEncontrado => write!(f, "no se encontro ninguna carta"),
+            Self::PaloInvalido(palo) => write!(f, "el palo {palo} no existe"),
+            Self::NumeroInvalido(num) => write!(f, "no hay cartas con el numero {num}"),
+        }
+    }
+}
+
 pub fn inicializar_emojis_palos(
     copa: EmojiId,
     espada: EmojiId,
@@ -72,6 +91,7 @@ pub enum Palo {
     Espada,
     Oro,
     Basto,
+    Comodin,
 }
 
 impl Palo {
@@ -81,20 +101,28 @@ impl Palo {
             Self::Espada => "espada",
             Self::Oro => "oro",
             Self::Basto => "basto",
+            Self::Comodin => "comodin",
         }
     }
 
-    pub fn emoji(&self) -> EmojiId {
-        EMOJIS_PALOS
-            .get()
-            .map(|ids| ids[*self as usize])
-            .unwrap_or_default()
+    pub fn emoji(&self) -> ReactionType {
+        match self {
+            Self::Comodin => ReactionType::Unicode(EMOJI_COMODIN.to_owned()),
+            _ => ReactionType::Custom {
+                animated: false,
+                id: EMOJIS_PALOS
+                    .get()
+                    .map(|ids| ids[*self as usize])
+                    .unwrap_or_default(),
+                name: Some(self.nombre().to_owned()),
+            },
+        }
     }
 }
 
 impl Display for Palo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "<:{}:{}>", self.nombre(), self.emoji().as_u64())
+        write!(f, "{}", self.emoji())
     }
 }
 
@@ -107,6 +135,7 @@ impl FromStr for Palo {
             "e" | "esp" | "espada" | "espadas" => Ok(Self::Espada),
             "c" | "copa" | "copas" => Ok(Self::Copa),
             "b" | "basto" | "bastos" | "p" | "palo" | "palos" => Ok(Self::Basto),
+            "comodin" | "comodines" | "joker" => Ok(Self::Comodin),
             _ => Err(PaloFromStrError {
                 string: s.to_owned(),
             }),
@@ -0,0 +1,166 @@
+use super::{
+    cartas::Carta,
+    partida::{Partida, PilaCartas, ReglasPartida},
+};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serenity::model::id::UserId;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Accion {
+    Repartir,
+    Levantar {
+        jugador: UserId,
+        pila: PilaCartas,
+    },
+    Tirar {
+        jugador: UserId,
+        carta: Carta,
+    },
+    Cortar {
+        jugador: UserId,
+        carta: Option<Carta>,
+    },
+    VotarExpulsar {
+        jugador: UserId,
+        a: UserId,
+    },
+    Abandonar {
+        jugador: UserId,
+    },
+    Reenganchar {
+        jugador: UserId,
+    },
+}
+
+impl Partida {
+    pub fn repetir(
+        jugadores: &[UserId],
+        reglas: ReglasPartida,
+        semilla: u64,
+        acciones: &[Accion],
+    ) -> Result<Self> {
+        let mut partida = Self::empezar_con_semilla(jugadores, reglas, semilla);
+        for accion in acciones {
+            partida
+                .aplicar(*accion)
+                .map_err(|e| anyhow!("No se puede repetir {:?}: {}", accion, e))?;
+        }
+        Ok(partida)
+    }
+
+    pub fn estado_en(&self, acciones: usize) -> Result<Self> {
+        let historial = self
+            .get_historial()
+            .get(..acciones)
+            .ok_or_else(|| anyhow!("La partida solo tiene {} acciones", acciones))?;
+        Self::repetir(
+            &self.get_jugadores(),
+            self.get_reglas(),
+            self.get_semilla(),
+            historial,
+        )
+    }
+
+    pub(super) fn aplicar(&mut self, accion: Accion) -> Result<()> {
+        let id = match accion {
+            Accion::Repartir => return Ok(()),
+            Accion::Reenganchar { jugador } => return self.reenganchar(jugador).map(|_| ()),
+            Accion::Levantar { jugador, .. }
+            | Accion::Tirar { jugador, .. }
+            | Accion::Cortar { jugador, .. }
+            | Accion::VotarExpulsar { jugador, .. }
+            | Accion::Abandonar { jugador } => jugador,
+        };
+        let mut jugador = self
+            .jugador(id)
+            .ok_or_else(|| anyhow!("{} no esta en la partida", id))?;
+        match accion {
+            Accion::Levantar { pila, .. } => {
+                jugador.levantar(pila).map_err(|e| anyhow!("{:?}", e))?;
+            }
+            Accion::Tirar { carta, .. } => jugador.tirar(carta).map_err(|e| anyhow!("{:?}", e))?,
+            Accion::Cortar { carta, .. } => {
+                jugador.cortar(carta).map_err(|e| anyhow!("{:?}", e))?;
+            }
+            Accion::VotarExpulsar { a, .. } => {
+                jugador.votar_expulsar_a(a)?;
+            }
+            Accion::Abandonar { .. } => jugador.abandonar(),
+            Accion::Repartir | Accion::Reenganchar { .. } => unreachable!(),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chinchon::{jugar_turno_bot, Dificultad};
+    use std::collections::HashMap;
+
+    type Foto = (
+        UserId,
+        HashMap<UserId, i16>,
+        Option<Carta>,
+        usize,
+        usize,
+        Vec<Option<Vec<Carta>>>,
+    );
+
+    fn foto(partida: &mut Partida) -> Foto {
+        let manos = partida
+            .get_jugadores()
+            .into_iter()
+            .map(|id| partida.jugador(id).map(|j| j.get_cartas()))
+            .collect();
+        (
+            partida.get_turno(),
+            partida.get_puntos(),
+            partida.get_descarte(),
+            partida.get_cartas_mazo(),
+            partida.get_rondas().len(),
+            manos,
+        )
+    }
+
+    #[test]
+    fn estado_en_coincide_con_la_partida_real() {
+        let ids: Vec<_> = (1..=3).map(UserId).collect();
+        let mut partida = Partida::empezar_con_semilla(&ids, ReglasPartida::default(), 11);
+        let mut fotos = vec![(partida.get_historial().len(), foto(&mut partida))];
+        while partida.ganador().is_none() {
+            let turno = partida.get_turno();
+            jugar_turno_bot(&mut partida.jugador(turno).unwrap(), Dificultad::Dificil);
+            fotos.push((partida.get_historial().len(), foto(&mut partida)));
+        }
+        for (acciones, esperada) in fotos {
+            let mut estado = partida.estado_en(acciones).unwrap();
+            assert_eq!(estado.get_historial(), &partida.get_historial()[..acciones]);
+            assert_eq!(foto(&mut estado), esperada, "tras {} acciones", acciones);
+        }
+        // El reparto lo agrega la partida sola al cortar, asi que puede quedar
+        // uno de mas al cortar el historial justo antes
+        for acciones in 1..=partida.get_historial().len() {
+            let estado = partida.estado_en(acciones).unwrap();
+            let (previas, extra) = estado.get_historial().split_at(acciones);
+            assert_eq!(previas, &partida.get_historial()[..acciones]);
+            assert!(extra.iter().all(|a| *a == Accion::Repartir), "{:?}", extra);
+        }
+        assert!(partida
+            .estado_en(partida.get_historial().len() + 1)
+            .is_err());
+    }
+
+    #[test]
+    fn repetir_rechaza_acciones_imposibles() {
+        let ids: Vec<_> = (1..=2).map(UserId).collect();
+        let partida = Partida::empezar_con_semilla(&ids, ReglasPartida::default(), 3);
+        let ajeno = ids.iter().find(|id| **id != partida.get_turno()).unwrap();
+        let acciones = [Accion::Levantar {
+            jugador: *ajeno,
+            pila: PilaCartas::Mazo,
+        }];
+        assert!(Partida::repetir(&ids, ReglasPartida::default(), 3, &acciones).is_err());
+    }
+}
@@ -1,8 +1,18 @@
+mod bot;
 mod buscar_juegos;
 mod cartas;
+mod historial;
+mod notacion;
 mod partida;
 
-pub use cartas::{inicializar_emojis_palos, Carta};
+pub use bot::{es_bot, id_bot, jugar_turno_bot, nombre_bot, Dificultad, JugadaBot, MAX_BOTS};
+pub use buscar_juegos::formar_juegos;
+#[cfg(feature = "comparar-busqueda")]
+pub use buscar_juegos::formar_juegos_exhaustivo;
+pub use cartas::{inicializar_emojis_palos, Carta, Palo};
+pub use historial::Accion;
 pub use partida::{
-    ErrorCortar, ErrorLevantar, ErrorTirar, Jugador, Partida, PilaCartas, ResultadoFinalRonda,
+    ErrorCortar, ErrorLevantar, ErrorTirar, ErrorValidacion, Jugador, Partida, PilaCartas,
+    PuntajeParejas, ReglasPartida, ResultadoFinalRonda, ResumenRonda, TipoMazo, TurnoVencido,
+    MAX_TURNOS_VENCIDOS,
 };
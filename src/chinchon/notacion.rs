@@ -0,0 +1,386 @@
+use super::{
+    cartas::{Carta, Palo},
+    historial::Accion,
+    partida::{Partida, PilaCartas, PuntajeParejas, ReglasPartida, TipoMazo},
+};
+use anyhow::{anyhow, Context, Result};
+use serenity::model::id::UserId;
+use std::{collections::HashMap, fmt::Write, str::FromStr};
+
+impl Partida {
+    pub fn notacion(&self) -> String {
+        self.escribir_notacion(true)
+    }
+
+    /// Notacion de una partida que sigue en juego: sin la semilla ni las manos
+    /// repartidas, asi no se pueden deducir las cartas de nadie.
+    pub fn notacion_en_curso(&self) -> String {
+        self.escribir_notacion(false)
+    }
+
+    fn escribir_notacion(&self, con_semilla: bool) -> String {
+        let jugadores = self.get_jugadores();
+        let reglas = self.get_reglas();
+        let nombre = |id: &UserId| match jugadores.iter().position(|j| j == id) {
+            Some(i) => format!("J{}", i + 1),
+            None => id.to_string(),
+        };
+        let mut texto = String::new();
+        if con_semilla {
+            let _ = writeln!(texto, "[Semilla \"{}\"]", self.get_semilla());
+        }
+        for (i, jugador) in jugadores.iter().enumerate() {
+            let _ = writeln!(texto, "[J{} \"{}\"]", i + 1, jugador);
+        }
+        for (etiqueta, valor) in etiquetas_reglas(&reglas) {
+            let _ = writeln!(texto, "[{} \"{}\"]", etiqueta, valor);
+        }
+        let mut repeticion = Self::empezar_con_semilla(&jugadores, reglas, self.get_semilla());
+        let mut sincronizada = true;
+        let mut ronda = 0;
+        for accion in self.get_historial() {
+            match accion {
+                Accion::Repartir => {
+                    ronda += 1;
+                    let _ = writeln!(texto, "\nRonda {}", ronda);
+                    if con_semilla && sincronizada {
+                        for jugador in &jugadores {
+                            if let Some(j) = repeticion.jugador(*jugador) {
+                                let cartas: Vec<_> =
+                                    j.get_cartas().iter().map(codigo_carta).collect();
+                                let _ =
+                                    writeln!(texto, "; {}: {}", nombre(jugador), cartas.join(" "));
+                            }
+                        }
+                    }
+                    continue;
+                }
+                Accion::Levantar { jugador, pila } => {
+                    let pila = match pila {
+                        PilaCartas::Mazo => "mazo",
+                        PilaCartas::Descartes => "descarte",
+                    };
+                    let _ = writeln!(texto, "{} levanta {}", nombre(jugador), pila);
+                }
+                Accion::Tirar { jugador, carta } => {
+                    let _ = writeln!(texto, "{} tira {}", nombre(jugador), codigo_carta(carta));
+                }
+                Accion::Cortar { jugador, carta } => {
+                    let _ = write!(texto, "{} corta", nombre(jugador));
+                    if let Some(carta) = carta {
+                        let _ = write!(texto, " {}", codigo_carta(carta));
+                    }
+                    texto.push('\n');
+                }
+                Accion::VotarExpulsar { jugador, a } => {
+                    let _ = writeln!(texto, "{} vota expulsar {}", nombre(jugador), nombre(a));
+                }
+                Accion::Abandonar { jugador } => {
+                    let _ = writeln!(texto, "{} abandona", nombre(jugador));
+                }
+                Accion::Reenganchar { jugador } => {
+                    let _ = writeln!(texto, "{} se reengancha", nombre(jugador));
+                }
+            }
+            sincronizada = sincronizada && repeticion.aplicar(*accion).is_ok();
+            if let (true, Accion::Cortar { .. }, Some(resumen)) =
+                (sincronizada, accion, repeticion.get_rondas().last())
+            {
+                let puntos: Vec<_> = resumen
+                    .puntos
+                    .iter()
+                    .map(|(j, sumados, total)| format!("{} {:+} = {}", nombre(j), sumados, total))
+                    .collect();
+                let _ = writeln!(texto, "; {}", puntos.join(", "));
+            }
+        }
+        if let Some(ganador) = self.ganador() {
+            let _ = writeln!(texto, "\n; Gana {}", nombre(&ganador));
+        }
+        texto
+    }
+
+    pub fn desde_notacion(texto: &str) -> Result<Self> {
+        let mut etiquetas = HashMap::new();
+        let mut acciones = vec![];
+        let mut jugadores: Vec<UserId> = vec![];
+        for (num_linea, linea) in texto.lines().enumerate() {
+            let error = || anyhow!("Linea {}: no entiendo \"{}\"", num_linea + 1, linea);
+            let linea = linea.trim();
+            if linea.is_empty() || linea.starts_with(';') {
+                continue;
+            }
+            if let Some(etiqueta) = linea.strip_prefix('[') {
+                let (nombre, valor) = etiqueta
+                    .strip_suffix(']')
+                    .and_then(|e| e.split_once(' '))
+                    .ok_or_else(error)?;
+                let valor = valor.trim().trim_matches('"').to_owned();
+                if let Some(num) = nombre.strip_prefix('J') {
+                    let num: usize = num.parse().map_err(|_| error())?;
+                    if num != jugadores.len() + 1 {
+                        return Err(error());
+                    }
+                    jugadores.push(valor.parse().map_err(|_| error())?);
+                } else {
+                    etiquetas.insert(nombre.to_owned(), valor);
+                }
+                continue;
+            }
+            if linea.starts_with("Ronda") {
+                acciones.push(Accion::Repartir);
+                continue;
+            }
+            let palabras: Vec<_> = linea.split_whitespace().collect();
+            let buscar = |nombre: &str| {
+                nombre
+                    .strip_prefix('J')
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .and_then(|n| jugadores.get(n.checked_sub(1)?))
+                    .copied()
+                    .ok_or_else(error)
+            };
+            let jugador = buscar(palabras[0])?;
+            let accion = match palabras[1..] {
+                ["levanta", "mazo"] => Accion::Levantar {
+                    jugador,
+                    pila: PilaCartas::Mazo,
+                },
+                ["levanta", "descarte"] => Accion::Levantar {
+                    jugador,
+                    pila: PilaCartas::Descartes,
+                },
+                ["tira", carta] => Accion::Tirar {
+                    jugador,
+                    carta: parsear_carta(carta).ok_or_else(error)?,
+                },
+                ["corta"] => Accion::Cortar {
+                    jugador,
+                    carta: None,
+                },
+                ["corta", carta] => Accion::Cortar {
+                    jugador,
+                    carta: Some(parsear_carta(carta).ok_or_else(error)?),
+                },
+                ["vota", "expulsar", a] => Accion::VotarExpulsar {
+                    jugador,
+                    a: buscar(a)?,
+                },
+                ["abandona"] => Accion::Abandonar { jugador },
+                ["se", "reengancha"] => Accion::Reenganchar { jugador },
+                _ => return Err(error()),
+            };
+            acciones.push(accion);
+        }
+        if jugadores.len() < 2 {
+            return Err(anyhow!("La partida necesita al menos 2 jugadores"));
+        }
+        let semilla = etiquetas
+            .get("Semilla")
+            .ok_or_else(|| anyhow!("Falta la etiqueta Semilla"))?
+            .parse()
+            .context("Semilla invalida")?;
+        let reglas = reglas_de_etiquetas(&etiquetas)?;
+        Self::repetir(&jugadores, reglas, semilla, &acciones)
+    }
+}
+
+fn etiquetas_reglas(reglas: &ReglasPartida) -> Vec<(&'static str, String)> {
+    let si_no = |b: bool| if b { "si" } else { "no" }.to_owned();
+    vec![
+        ("Limite", reglas.limite_puntos.to_string()),
+        ("CortaCon", reglas.max_puntos_cortar.to_string()),
+        ("CorteCero", reglas.puntos_cortar_cero.to_string()),
+        ("Chinchon", reglas.penalizacion_chinchon.to_string()),
+        (
+            "Comodines",
+            if reglas.comodines {
+                reglas.puntos_comodin.to_string()
+            } else {
+                "no".to_owned()
+            },
+        ),
+        (
+            "Mazo",
+            match reglas.mazo {
+                TipoMazo::De48 => "48",
+                TipoMazo::De40 => "40",
+            }
+            .to_owned(),
+        ),
+        ("Reenganches", reglas.max_reenganches.to_string()),
+        ("Acomodar", si_no(reglas.acomodar)),
+        (
+            "Parejas",
+            if reglas.parejas {
+                reglas.puntaje_parejas.nombre().to_owned()
+            } else {
+                "no".to_owned()
+            },
+        ),
+        (
+            "Tiempo",
+            reglas
+                .segundos_turno
+                .map_or_else(|| "no".to_owned(), |s| s.to_string()),
+        ),
+    ]
+}
+
+fn reglas_de_etiquetas(etiquetas: &HashMap<String, String>) -> Result<ReglasPartida> {
+    fn leer<T: FromStr>(
+        etiquetas: &HashMap<String, String>,
+        nombre: &str,
+        reglas: &mut T,
+    ) -> Result<()> {
+        if let Some(valor) = etiquetas.get(nombre) {
+            *reglas = valor
+                .parse()
+                .map_err(|_| anyhow!("Valor invalido para {}: {}", nombre, valor))?;
+        }
+        Ok(())
+    }
+    let mut reglas = ReglasPartida::default();
+    leer(etiquetas, "Limite", &mut reglas.limite_puntos)?;
+    leer(etiquetas, "CortaCon", &mut reglas.max_puntos_cortar)?;
+    leer(etiquetas, "CorteCero", &mut reglas.puntos_cortar_cero)?;
+    leer(etiquetas, "Chinchon", &mut reglas.penalizacion_chinchon)?;
+    leer(etiquetas, "Reenganches", &mut reglas.max_reenganches)?;
+    if let Some(valor) = etiquetas.get("Comodines").filter(|v| *v != "no") {
+        reglas.comodines = true;
+        reglas.puntos_comodin = valor
+            .parse()
+            .map_err(|_| anyhow!("Valor invalido para Comodines: {}", valor))?;
+    }
+    match etiquetas.get("Mazo").map(String::as_str) {
+        None | Some("48") => {}
+        Some("40") => reglas.mazo = TipoMazo::De40,
+        Some(otro) => return Err(anyhow!("No hay mazos de {} cartas", otro)),
+    }
+    reglas.acomodar = etiquetas.get("Acomodar").is_some_and(|v| v == "si");
+    match etiquetas.get("Parejas").map(String::as_str) {
+        None | Some("no") => {}
+        Some(nombre) => {
+            reglas.parejas = true;
+            reglas.puntaje_parejas = [PuntajeParejas::Suma, PuntajeParejas::Maximo]
+                .into_iter()
+                .find(|p| p.nombre() == nombre)
+                .ok_or_else(|| anyhow!("Valor invalido para Parejas: {}", nombre))?;
+        }
+    }
+    if let Some(valor) = etiquetas.get("Tiempo").filter(|v| *v != "no") {
+        reglas.segundos_turno = Some(
+            valor
+                .parse()
+                .map_err(|_| anyhow!("Valor invalido para Tiempo: {}", valor))?,
+        );
+    }
+    Ok(reglas)
+}
+
+fn codigo_carta(carta: &Carta) -> String {
+    let palo = match carta.palo {
+        Palo::Copa => 'c',
+        Palo::Espada => 'e',
+        Palo::Oro => 'o',
+        Palo::Basto => 'b',
+        Palo::Comodin => 'j',
+    };
+    format!("{}{}", carta.num, palo)
+}
+
+fn parsear_carta(codigo: &str) -> Option<Carta> {
+    let (indice, palo) = codigo.char_indices().last()?;
+    let num = &codigo[..indice];
+    let palo = match palo {
+        'c' => Palo::Copa,
+        'e' => Palo::Espada,
+        'o' => Palo::Oro,
+        'b' => Palo::Basto,
+        'j' => Palo::Comodin,
+        _ => return None,
+    };
+    Some(Carta {
+        num: num.parse().ok().filter(|n| (1..=12).contains(n))?,
+        palo,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chinchon::{jugar_turno_bot, Dificultad};
+
+    fn partida_jugada(semilla: u64) -> Partida {
+        let ids: Vec<_> = (1..=3).map(UserId).collect();
+        let mut partida = Partida::empezar_con_semilla(&ids, ReglasPartida::default(), semilla);
+        while partida.ganador().is_none() {
+            let turno = partida.get_turno();
+            jugar_turno_bot(&mut partida.jugador(turno).unwrap(), Dificultad::Dificil);
+        }
+        partida
+    }
+
+    #[test]
+    fn notacion_ida_y_vuelta() {
+        let partida = partida_jugada(7);
+        let notacion = partida.notacion();
+        let repetida = Partida::desde_notacion(&notacion).unwrap();
+        assert_eq!(repetida.get_historial(), partida.get_historial());
+        assert_eq!(repetida.get_puntos(), partida.get_puntos());
+        assert_eq!(repetida.ganador(), partida.ganador());
+        assert_eq!(repetida.notacion(), notacion);
+    }
+
+    #[test]
+    fn notacion_en_curso_no_muestra_cartas() {
+        let partida = partida_jugada(7);
+        let en_curso = partida.notacion_en_curso();
+        assert!(!en_curso.contains("[Semilla"));
+        assert!(!en_curso.lines().any(|l| l.starts_with("; J1:")));
+        assert!(partida.notacion().lines().any(|l| l.starts_with("; J1:")));
+        let jugadas = |texto: &str| -> Vec<String> {
+            texto
+                .lines()
+                .filter(|l| !l.starts_with(';') && !l.starts_with('['))
+                .map(str::to_owned)
+                .collect()
+        };
+        assert_eq!(jugadas(&en_curso), jugadas(&partida.notacion()));
+    }
+
+    #[test]
+    fn notacion_invalida_da_error() {
+        let notacion = partida_jugada(7).notacion();
+        let invalidas = [
+            String::new(),
+            "esto no es una partida".to_owned(),
+            notacion.replacen("tira", "tira 1ñ ;", 1),
+            notacion.replacen("tira ", "tira 1ñ", 1),
+            notacion.replacen("tira ", "tira 13", 1),
+            notacion.replacen("J1 ", "J9 ", 1),
+            notacion.replacen("[Semilla", "[Semill", 1),
+        ];
+        for invalida in invalidas {
+            assert!(
+                Partida::desde_notacion(&invalida).is_err(),
+                "deberia fallar: {}",
+                invalida
+            );
+        }
+    }
+
+    #[test]
+    fn cartas_con_caracteres_multibyte() {
+        assert_eq!(parsear_carta("1ñ"), None);
+        assert_eq!(parsear_carta("ñ"), None);
+        assert_eq!(parsear_carta(""), None);
+        assert_eq!(
+            parsear_carta("12o"),
+            Some(Carta {
+                num: 12,
+                palo: Palo::Oro
+            })
+        );
+    }
+}
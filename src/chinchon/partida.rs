@@ -1,19 +1,32 @@
 use super::{
-    buscar_juegos::formar_juegos,
+    bot::Dificultad,
+    buscar_juegos::{acomodar_cartas, formar_juegos},
     cartas::{Carta, Palo},
+    historial::Accion,
 };
 use anyhow::{anyhow, Result};
-use rand::{prelude::SliceRandom, thread_rng};
+use rand::{prelude::SliceRandom, thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 use serenity::model::id::UserId;
 use std::{
-    collections::{HashMap, HashSet},
-    mem::{swap, take},
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt::Display,
+    mem::{replace, swap, take},
     time::{Duration, SystemTime},
 };
 
+pub const MAX_TURNOS_VENCIDOS: u8 = 3;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Partida {
+    #[serde(default)]
+    reglas: ReglasPartida,
+    #[serde(default)]
+    semilla: u64,
+    #[serde(default = "ChaCha8Rng::from_entropy")]
+    rng: ChaCha8Rng,
     tiempo_ultima_jugada: SystemTime,
     mazo: Vec<Carta>,
     descartes: Vec<Carta>,
@@ -21,11 +34,26 @@ pub struct Partida {
     jugadores: Vec<DatosJugador>,
     turno: usize,
     inicia_prox_ronda: usize,
+    #[serde(default)]
+    historial: Vec<Accion>,
+    #[serde(default)]
+    turno_avisado: bool,
+    #[serde(default)]
+    recordatorios_turno: u32,
+    #[serde(default)]
+    rondas: Vec<ResumenRonda>,
 }
 
 impl Partida {
-    pub fn empezar(jugadores: &[UserId]) -> Self {
+    pub fn empezar(jugadores: &[UserId], reglas: ReglasPartida) -> Self {
+        Self::empezar_con_semilla(jugadores, reglas, thread_rng().gen())
+    }
+
+    pub fn empezar_con_semilla(jugadores: &[UserId], reglas: ReglasPartida, semilla: u64) -> Self {
         let mut s = Self {
+            reglas,
+            semilla,
+            rng: ChaCha8Rng::seed_from_u64(semilla),
             tiempo_ultima_jugada: SystemTime::now(),
             jugadores: jugadores
                 .iter()
@@ -39,27 +67,54 @@ impl Partida {
             pila_ultimo_levante: None,
             turno: 0,
             inicia_prox_ronda: 0,
+            historial: vec![],
+            turno_avisado: false,
+            recordatorios_turno: 0,
+            rondas: vec![],
         };
         s.comenzar_ronda();
+        s.comprobar();
         s
     }
 
     fn comenzar_ronda(&mut self) {
         self.descartes.clear();
-        self.mazo = mazo_mezclado();
+        self.mazo = mazo_mezclado(&self.reglas, &mut self.rng);
         self.pila_ultimo_levante = None;
+        while self.jugadores[self.inicia_prox_ronda].perdio(&self.reglas) {
+            self.inicia_prox_ronda = (self.inicia_prox_ronda + 1) % self.jugadores.len();
+        }
+        let reglas = self.reglas;
         for (i, jugador) in self.jugadores.iter_mut().enumerate() {
-            jugador.mano = self
-                .mazo
-                .drain(0..if i == self.inicia_prox_ronda { 8 } else { 7 })
-                .collect();
+            jugador.mano = if jugador.perdio(&reglas) {
+                vec![]
+            } else {
+                self.mazo
+                    .drain(0..if i == self.inicia_prox_ronda { 8 } else { 7 })
+                    .collect()
+            };
             jugador.mano.sort_unstable();
         }
-        while self.jugadores[self.inicia_prox_ronda].perdio() {
-            self.inicia_prox_ronda = (self.inicia_prox_ronda + 1) % self.jugadores.len();
-        }
         self.turno = self.inicia_prox_ronda;
         self.inicia_prox_ronda = (self.inicia_prox_ronda + 1) % self.jugadores.len();
+        self.tiempo_ultima_jugada = SystemTime::now();
+        self.turno_avisado = false;
+        self.recordatorios_turno = 0;
+        self.historial.push(Accion::Repartir);
+    }
+
+    fn repartir_a(&mut self, id: UserId) {
+        if self.mazo.len() < 7 {
+            let tope = self.descartes.pop();
+            self.mazo.append(&mut self.descartes);
+            self.descartes.extend(tope);
+            self.mazo.shuffle(&mut self.rng);
+        }
+        let mano: Vec<_> = self.mazo.drain(0..7).collect();
+        if let Some(jugador) = self.jugadores.iter_mut().find(|j| j.id == id) {
+            jugador.mano = mano;
+            jugador.mano.sort_unstable();
+        }
     }
 
     pub fn tiempo_inactiva(&self) -> Duration {
@@ -70,14 +125,28 @@ impl Partida {
 
     fn pasar_turno(&mut self) {
         self.tiempo_ultima_jugada = SystemTime::now();
+        self.turno_avisado = false;
+        self.recordatorios_turno = 0;
         loop {
             self.turno = (self.turno + 1) % self.jugadores.len();
-            if !self.jugadores[self.turno].perdio() {
+            if !self.jugadores[self.turno].perdio(&self.reglas) {
                 break;
             }
         }
     }
 
+    pub fn avisar_turno(&mut self) -> bool {
+        !replace(&mut self.turno_avisado, true)
+    }
+
+    pub fn recordar_turno(&mut self, cada: Duration) -> bool {
+        let toca = self.tiempo_inactiva() >= cada * (self.recordatorios_turno + 1);
+        if toca {
+            self.recordatorios_turno += 1;
+        }
+        toca
+    }
+
     pub fn get_descarte(&self) -> Option<Carta> {
         self.descartes.last().cloned()
     }
@@ -90,31 +159,163 @@ impl Partida {
         self.jugadores.iter().map(|j| (j.id, j.puntos)).collect()
     }
 
+    pub fn get_cartas_mazo(&self) -> usize {
+        self.mazo.len()
+    }
+
+    pub fn get_rondas(&self) -> &[ResumenRonda] {
+        &self.rondas
+    }
+
+    pub fn esta_en_juego(&self, id: UserId) -> bool {
+        self.jugadores
+            .iter()
+            .any(|j| j.id == id && !j.perdio(&self.reglas))
+    }
+
     pub fn get_pila_ultimo_levante(&self) -> Option<PilaCartas> {
         self.pila_ultimo_levante
     }
 
+    pub fn get_jugadores(&self) -> Vec<UserId> {
+        self.jugadores.iter().map(|j| j.id).collect()
+    }
+
+    pub fn get_historial(&self) -> &[Accion] {
+        &self.historial
+    }
+
+    pub fn set_bot(&mut self, id: UserId, dificultad: Dificultad) {
+        if let Some(jugador) = self.jugadores.iter_mut().find(|j| j.id == id) {
+            jugador.bot = Some(dificultad);
+        }
+    }
+
+    pub fn get_bot(&self, id: UserId) -> Option<Dificultad> {
+        self.jugadores
+            .iter()
+            .find(|j| j.id == id)
+            .and_then(|j| j.bot)
+    }
+
+    pub fn get_semilla(&self) -> u64 {
+        self.semilla
+    }
+
+    pub fn get_reglas(&self) -> ReglasPartida {
+        self.reglas
+    }
+
     pub fn jugadores_en_juego(&self) -> usize {
-        self.jugadores.iter().filter(|j| !j.perdio()).count()
+        self.jugadores
+            .iter()
+            .filter(|j| !j.perdio(&self.reglas))
+            .count()
     }
 
     fn buscar_jugador(&mut self, id: UserId) -> Option<(usize, &mut DatosJugador)> {
+        let reglas = self.reglas;
         self.jugadores
             .iter_mut()
             .enumerate()
-            .find(|(_, j)| j.id == id && !j.perdio())
+            .find(|(_, j)| j.id == id && !j.perdio(&reglas))
     }
 
     pub fn ganador(&self) -> Option<UserId> {
-        let mut no_perdieron = self.jugadores.iter().filter(|j| !j.perdio());
-        let posible_ganador = no_perdieron.next();
-        if no_perdieron.next().is_none() {
-            posible_ganador.map(|j| j.id)
+        let mut no_perdieron = self
+            .jugadores
+            .iter()
+            .enumerate()
+            .filter(|(_, j)| !j.perdio(&self.reglas));
+        let (indice, posible_ganador) = no_perdieron.next()?;
+        if no_perdieron.all(|(i, _)| Some(i) == self.indice_pareja(indice)) {
+            Some(posible_ganador.id)
         } else {
             None
         }
     }
 
+    fn indice_pareja(&self, indice: usize) -> Option<usize> {
+        (self.reglas.parejas && self.jugadores.len() == 4).then_some((indice + 2) % 4)
+    }
+
+    pub fn get_pareja(&self, id: UserId) -> Option<UserId> {
+        let indice = self.jugadores.iter().position(|j| j.id == id)?;
+        self.indice_pareja(indice).map(|i| self.jugadores[i].id)
+    }
+
+    pub fn get_puntos_pareja(&self, id: UserId) -> Option<i16> {
+        let indice = self.jugadores.iter().position(|j| j.id == id)?;
+        self.indice_pareja(indice).map(|i| {
+            self.reglas
+                .puntaje_parejas
+                .total(self.jugadores[indice].puntos, self.jugadores[i].puntos)
+        })
+    }
+
+    pub fn posiciones(&self) -> Vec<Vec<UserId>> {
+        let ronda_eliminado = |j: &DatosJugador| {
+            j.ronda_eliminado
+                .map_or((usize::MAX, false), |r| (r, j.salio_a_mitad_de_ronda))
+        };
+        let mut jugadores: Vec<_> = self.jugadores.iter().collect();
+        jugadores.sort_by_key(|j| Reverse(ronda_eliminado(j)));
+        jugadores
+            .chunk_by(|a, b| ronda_eliminado(a) == ronda_eliminado(b))
+            .map(|grupo| grupo.iter().map(|j| j.id).collect())
+            .collect()
+    }
+
+    /// Quienes se van a mitad de ronda quedan por encima de los que
+    /// perdieron al final de la ronda anterior.
+    fn marcar_eliminados(&mut self, a_mitad_de_ronda: bool) {
+        let ronda = self.rondas.len();
+        for jugador in &mut self.jugadores {
+            if jugador.perdio(&self.reglas) && jugador.ronda_eliminado.is_none() {
+                jugador.ronda_eliminado = Some(ronda);
+                jugador.salio_a_mitad_de_ronda = a_mitad_de_ronda;
+            }
+        }
+    }
+
+    fn eliminar_pareja_de(&mut self, indice: usize) {
+        if let Some(i) = self.indice_pareja(indice) {
+            let pareja = &mut self.jugadores[i];
+            pareja.pareja_perdio = true;
+            let cartas = take(&mut pareja.mano);
+            self.mazo.extend(cartas);
+        }
+    }
+
+    pub fn reenganchar(&mut self, id: UserId) -> Result<i16> {
+        let puntos = self
+            .jugadores
+            .iter()
+            .filter(|j| !j.perdio(&self.reglas))
+            .map(|j| j.puntos)
+            .max()
+            .unwrap_or_default();
+        let jugador = self
+            .jugadores
+            .iter_mut()
+            .find(|j| j.id == id)
+            .ok_or_else(|| anyhow!("No estas en esta partida corazon :c"))?;
+        if !jugador.puede_reengancharse {
+            return Err(anyhow!("No te puedes reenganchar ahora :("));
+        }
+        jugador.puede_reengancharse = false;
+        jugador.reenganches += 1;
+        jugador.puntos = puntos;
+        jugador.ronda_eliminado = None;
+        jugador.salio_a_mitad_de_ronda = false;
+        if jugador.mano.is_empty() {
+            self.repartir_a(id);
+        }
+        self.historial.push(Accion::Reenganchar { jugador: id });
+        self.comprobar();
+        Ok(puntos)
+    }
+
     pub fn jugador(&mut self, id: UserId) -> Option<Jugador<'_>> {
         let (indice, _) = self.buscar_jugador(id)?;
         Some(Jugador {
@@ -122,6 +323,163 @@ impl Partida {
             partida: self,
         })
     }
+
+    pub fn validar(&self) -> Result<(), Vec<ErrorValidacion>> {
+        let mut errores = vec![];
+        if self.jugadores.len() < 2 {
+            errores.push(ErrorValidacion::PocosJugadores(self.jugadores.len()));
+        }
+        let mut cartas: BTreeMap<Carta, i32> = BTreeMap::new();
+        for carta in cartas_mazo(&self.reglas) {
+            *cartas.entry(carta).or_default() -= 1;
+        }
+        let en_juego = self
+            .mazo
+            .iter()
+            .chain(&self.descartes)
+            .chain(self.jugadores.iter().flat_map(|j| &j.mano));
+        for carta in en_juego {
+            *cartas.entry(*carta).or_default() += 1;
+        }
+        let esperadas: BTreeSet<_> = cartas_mazo(&self.reglas).into_iter().collect();
+        for (carta, diferencia) in cartas {
+            if diferencia < 0 {
+                errores.push(ErrorValidacion::CartaFaltante(carta));
+            } else if diferencia > 0 && !esperadas.contains(&carta) {
+                errores.push(ErrorValidacion::CartaDesconocida(carta));
+            } else if diferencia > 0 {
+                errores.push(ErrorValidacion::CartaRepetida(carta));
+            }
+        }
+        for (i, jugador) in self.jugadores.iter().enumerate() {
+            if jugador.mano.len() > 8 {
+                errores.push(ErrorValidacion::ManoMuyGrande(
+                    jugador.id,
+                    jugador.mano.len(),
+                ));
+            } else if jugador.mano.len() == 8 && i != self.turno {
+                errores.push(ErrorValidacion::OchoCartasSinTurno(jugador.id));
+            } else if jugador.mano.len() < 7 && !jugador.perdio(&self.reglas) {
+                errores.push(ErrorValidacion::ManoIncompleta(
+                    jugador.id,
+                    jugador.mano.len(),
+                ));
+            }
+        }
+        match self.jugadores.get(self.turno) {
+            None => errores.push(ErrorValidacion::TurnoInexistente(self.turno)),
+            Some(jugador) if jugador.perdio(&self.reglas) => {
+                errores.push(ErrorValidacion::TurnoDeEliminado(jugador.id))
+            }
+            _ => {}
+        }
+        if self.inicia_prox_ronda >= self.jugadores.len() {
+            errores.push(ErrorValidacion::InicioInexistente(self.inicia_prox_ronda));
+        }
+        if errores.is_empty() {
+            Ok(())
+        } else {
+            Err(errores)
+        }
+    }
+
+    fn comprobar(&self) {
+        if cfg!(debug_assertions) {
+            if let Err(errores) = self.validar() {
+                let errores: Vec<_> = errores.iter().map(|e| e.to_string()).collect();
+                eprintln!("Partida inconsistente: {}", errores.join(", "));
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReglasPartida {
+    pub limite_puntos: i16,
+    pub max_puntos_cortar: i16,
+    pub puntos_cortar_cero: i16,
+    pub penalizacion_chinchon: i16,
+    pub comodines: bool,
+    pub puntos_comodin: i16,
+    pub mazo: TipoMazo,
+    pub max_reenganches: u8,
+    pub acomodar: bool,
+    pub parejas: bool,
+    pub puntaje_parejas: PuntajeParejas,
+    pub segundos_turno: Option<u32>,
+}
+
+impl ReglasPartida {
+    pub fn puntos_carta(&self, carta: &Carta) -> i16 {
+        if carta.es_comodin() {
+            self.puntos_comodin
+        } else {
+            carta.num as i16
+        }
+    }
+}
+
+impl Default for ReglasPartida {
+    fn default() -> Self {
+        Self {
+            limite_puntos: 100,
+            max_puntos_cortar: 5,
+            puntos_cortar_cero: -10,
+            penalizacion_chinchon: 1000,
+            comodines: false,
+            puntos_comodin: 25,
+            mazo: TipoMazo::De48,
+            max_reenganches: 0,
+            acomodar: false,
+            parejas: false,
+            puntaje_parejas: PuntajeParejas::Suma,
+            segundos_turno: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PuntajeParejas {
+    Suma,
+    Maximo,
+}
+
+impl PuntajeParejas {
+    pub const fn total(&self, a: i16, b: i16) -> i16 {
+        match self {
+            Self::Suma => a + b,
+            Self::Maximo => {
+                if a > b {
+                    a
+                } else {
+                    b
+                }
+            }
+        }
+    }
+
+    pub const fn nombre(&self) -> &'static str {
+        match self {
+            Self::Suma => "suma",
+            Self::Maximo => "maximo",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TipoMazo {
+    De48,
+    De40,
+}
+
+impl TipoMazo {
+    pub const fn numeros(&self) -> &'static [u8] {
+        match self {
+            Self::De48 => &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
+            Self::De40 => &[1, 2, 3, 4, 5, 6, 7, 10, 11, 12],
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -129,19 +487,32 @@ struct DatosJugador {
     id: UserId,
     mano: Vec<Carta>,
     puntos: i16,
-    #[serde(skip)]
-    #[serde(default = "HashSet::new")]
+    #[serde(default)]
     votos_expulsar: HashSet<UserId>,
     eliminado: bool,
+    #[serde(default)]
+    reenganches: u8,
+    #[serde(default)]
+    puede_reengancharse: bool,
+    #[serde(default)]
+    bot: Option<Dificultad>,
+    #[serde(default)]
+    pareja_perdio: bool,
+    #[serde(default)]
+    turnos_vencidos: u8,
+    #[serde(default)]
+    ronda_eliminado: Option<usize>,
+    #[serde(default)]
+    salio_a_mitad_de_ronda: bool,
 }
 
 impl DatosJugador {
-    const fn perdio(&self) -> bool {
-        self.puntos > 100 || self.eliminado
+    const fn perdio(&self, reglas: &ReglasPartida) -> bool {
+        self.puntos > reglas.limite_puntos || self.eliminado || self.pareja_perdio
     }
 
-    const fn pierde_sumando(&self, suma: i16) -> bool {
-        (self.puntos + suma) > 100 || self.eliminado
+    const fn pierde_sumando(&self, suma: i16, reglas: &ReglasPartida) -> bool {
+        (self.puntos + suma) > reglas.limite_puntos || self.eliminado || self.pareja_perdio
     }
 
     fn votar_expulsar(&mut self, jugador: UserId, total_jugadores: usize) -> bool {
@@ -166,6 +537,10 @@ impl<'a> Jugador<'a> {
         &self.partida.jugadores[self.indice]
     }
 
+    pub fn get_id(&self) -> UserId {
+        self.datos().id
+    }
+
     pub fn es_turno(&self) -> bool {
         self.partida.turno == self.indice
     }
@@ -179,14 +554,36 @@ impl<'a> Jugador<'a> {
         if let Ok(i) = mano.binary_search(&con) {
             mano.remove(i);
         }
-        let (puntos_sumados, _) = formar_juegos(mano);
-        puntos_sumados <= 5 && !self.datos().pierde_sumando(puntos_sumados)
+        let reglas = &self.partida.reglas;
+        let (puntos_sumados, _) = formar_juegos(mano, reglas);
+        let pierde_pareja = self.partida.indice_pareja(self.indice).is_some_and(|i| {
+            let pareja = &self.partida.jugadores[i];
+            let (puntos_pareja, _) = formar_juegos(pareja.mano.clone(), reglas);
+            reglas.puntaje_parejas.total(
+                self.datos().puntos + puntos_sumados,
+                pareja.puntos + puntos_pareja,
+            ) > reglas.limite_puntos
+        });
+        puntos_sumados <= reglas.max_puntos_cortar
+            && !self.datos().pierde_sumando(puntos_sumados, reglas)
+            && !pierde_pareja
     }
 
     fn datos_mut(&mut self) -> &mut DatosJugador {
         &mut self.partida.jugadores[self.indice]
     }
 
+    fn devolver_carta(&mut self, carta: Option<Carta>) {
+        if let Some(carta) = carta {
+            let pos = self
+                .datos()
+                .mano
+                .binary_search(&carta)
+                .unwrap_or_else(|e| e);
+            self.datos_mut().mano.insert(pos, carta);
+        }
+    }
+
     pub fn tirar(&mut self, carta: Carta) -> Result<(), ErrorTirar> {
         if !self.es_turno() {
             return Err(ErrorTirar::NoEsTurno);
@@ -199,11 +596,43 @@ impl<'a> Jugador<'a> {
             .binary_search(&carta)
             .map_err(|_| ErrorTirar::NoTieneCarta)?;
         mano.remove(ind_carta);
+        self.datos_mut().turnos_vencidos = 0;
         self.partida.descartes.push(carta);
+        self.partida.historial.push(Accion::Tirar {
+            jugador: self.datos().id,
+            carta,
+        });
         self.partida.pasar_turno();
+        self.partida.comprobar();
         Ok(())
     }
 
+    pub fn jugar_turno_vencido(&mut self) -> TurnoVencido {
+        let vencidos = self.datos().turnos_vencidos + 1;
+        if vencidos >= MAX_TURNOS_VENCIDOS {
+            self.abandonar();
+            return TurnoVencido::Expulsado;
+        }
+        let mut levanto = None;
+        if self.datos().mano.len() < 8 {
+            self.levantar(PilaCartas::Mazo).unwrap();
+            levanto = Some(PilaCartas::Mazo);
+        }
+        let reglas = self.partida.reglas;
+        let mano = self.get_cartas();
+        let (_, juegos) = formar_juegos(mano.clone(), &reglas);
+        let carta = mano
+            .iter()
+            .filter(|c| juegos.iter().flatten().all(|cj| cj != *c))
+            .max_by_key(|c| reglas.puntos_carta(c))
+            .or_else(|| mano.iter().max_by_key(|c| reglas.puntos_carta(c)))
+            .copied()
+            .unwrap();
+        self.tirar(carta).unwrap();
+        self.partida.jugadores[self.indice].turnos_vencidos = vencidos;
+        TurnoVencido::Jugado { levanto, carta }
+    }
+
     pub fn levantar(&mut self, pila: PilaCartas) -> Result<Carta, ErrorLevantar> {
         if !self.es_turno() {
             return Err(ErrorLevantar::NoEsTurno);
@@ -213,6 +642,7 @@ impl<'a> Jugador<'a> {
             descartes,
             jugadores,
             pila_ultimo_levante,
+            rng,
             ..
         } = self.partida;
         let mano = &mut jugadores[self.indice].mano;
@@ -223,7 +653,7 @@ impl<'a> Jugador<'a> {
             if mazo.is_empty() {
                 swap(mazo, descartes);
                 descartes.push(mazo.pop().unwrap());
-                mazo.shuffle(&mut thread_rng());
+                mazo.shuffle(rng);
             }
             Ok(mazo.pop().unwrap())
         } else {
@@ -232,6 +662,11 @@ impl<'a> Jugador<'a> {
         let pos = mano.binary_search(&carta).unwrap_or_else(|e| e);
         mano.insert(pos, carta);
         *pila_ultimo_levante = Some(pila);
+        self.partida.historial.push(Accion::Levantar {
+            jugador: self.datos().id,
+            pila,
+        });
+        self.partida.comprobar();
         Ok(carta)
     }
 
@@ -255,20 +690,21 @@ impl<'a> Jugador<'a> {
         } else if self.datos().mano.len() >= 8 {
             return Err(ErrorCortar::DebeBajar);
         }
+        let reglas = self.partida.reglas;
         let mut resultados: Vec<_> = self
             .partida
             .jugadores
             .iter()
             .enumerate()
             .map(|(i, j)| {
-                if j.perdio() {
+                if j.perdio(&reglas) {
                     return None;
                 }
-                let (mut puntos_sumados, juegos) = formar_juegos(j.mano.clone());
+                let (mut puntos_sumados, juegos) = formar_juegos(j.mano.clone(), &reglas);
                 let mut chinchon = false;
                 if i == self.indice && puntos_sumados == 0 {
-                    puntos_sumados = -10;
-                    if juegos.len() == 1 {
+                    puntos_sumados = reglas.puntos_cortar_cero;
+                    if juegos.len() == 1 && !juegos[0].iter().any(|c| c.es_comodin()) {
                         chinchon = true;
                     }
                 }
@@ -276,8 +712,12 @@ impl<'a> Jugador<'a> {
                     jugador: j.id,
                     puntos_sumados,
                     puntos_total: (j.puntos + puntos_sumados).max(0),
-                    perdio: j.pierde_sumando(puntos_sumados),
+                    perdio: j.pierde_sumando(puntos_sumados, &reglas),
+                    puede_reengancharse: false,
+                    pareja: None,
+                    puntos_pareja: None,
                     chinchon,
+                    acomodadas: vec![],
                     sobrantes: j
                         .mano
                         .iter()
@@ -289,38 +729,111 @@ impl<'a> Jugador<'a> {
             })
             .collect();
         let resul_propio = resultados[self.indice].clone().unwrap();
-        if resul_propio.puntos_sumados > 5 || resul_propio.perdio {
-            if let Some(carta) = carta {
-                let pos = self
-                    .datos()
-                    .mano
-                    .binary_search(&carta)
-                    .unwrap_or_else(|e| e);
-                self.datos_mut().mano.insert(pos, carta);
-            }
+        if resul_propio.puntos_sumados > reglas.max_puntos_cortar || resul_propio.perdio {
+            self.devolver_carta(carta);
             return Err(ErrorCortar::PuntajeMuyAlto);
         } else if resul_propio.chinchon {
+            let pareja = self.partida.get_pareja(resul_propio.jugador);
             for resul in resultados.iter_mut().flat_map(|r| r.as_mut()) {
-                if resul.jugador != resul_propio.jugador {
-                    resul.puntos_sumados += 1000;
-                    resul.puntos_total += 1000;
+                if resul.jugador != resul_propio.jugador && Some(resul.jugador) != pareja {
+                    resul.puntos_sumados += reglas.penalizacion_chinchon;
+                    resul.puntos_total += reglas.penalizacion_chinchon;
                     resul.perdio = true;
                 }
             }
+        } else if reglas.acomodar {
+            let mut juegos_corto = resul_propio.juegos.clone();
+            for (jugador, resul) in self.partida.jugadores.iter().zip(resultados.iter_mut()) {
+                let resul = match resul {
+                    Some(resul) if resul.jugador != resul_propio.jugador => resul,
+                    _ => continue,
+                };
+                resul.acomodadas =
+                    acomodar_cartas(&mut resul.sobrantes, &mut juegos_corto, &reglas)
+                        .into_iter()
+                        .map(|(carta, juego)| CartaAcomodada {
+                            carta,
+                            jugador: resul_propio.jugador,
+                            juego,
+                        })
+                        .collect();
+                resul.puntos_sumados -= resul
+                    .acomodadas
+                    .iter()
+                    .map(|a| reglas.puntos_carta(&a.carta))
+                    .sum::<i16>();
+                resul.puntos_total = (jugador.puntos + resul.puntos_sumados).max(0);
+                resul.perdio = jugador.pierde_sumando(resul.puntos_sumados, &reglas);
+            }
+        }
+        let totales_parejas: Vec<_> = (0..resultados.len())
+            .map(|i| {
+                let pareja = self.partida.indice_pareja(i)?;
+                match (&resultados[i], &resultados[pareja]) {
+                    (Some(propio), Some(de_pareja)) => Some((
+                        de_pareja.jugador,
+                        reglas
+                            .puntaje_parejas
+                            .total(propio.puntos_total, de_pareja.puntos_total),
+                        propio.perdio || de_pareja.perdio,
+                    )),
+                    _ => None,
+                }
+            })
+            .collect();
+        for (resultado, total) in resultados.iter_mut().zip(totales_parejas) {
+            if let (Some(resultado), Some((pareja, puntos, perdio))) = (resultado, total) {
+                resultado.pareja = Some(pareja);
+                resultado.puntos_pareja = Some(puntos);
+                resultado.perdio = perdio || puntos > reglas.limite_puntos;
+            }
+        }
+        if resultados[self.indice].as_ref().unwrap().perdio {
+            self.devolver_carta(carta);
+            return Err(ErrorCortar::PuntajeMuyAlto);
         }
         for (jugador, resultado) in self.partida.jugadores.iter_mut().zip(resultados.iter()) {
+            jugador.puede_reengancharse = false;
             if let Some(resultado) = resultado {
                 jugador.puntos = resultado.puntos_total;
+                jugador.pareja_perdio = resultado.perdio && resultado.pareja.is_some();
+            }
+        }
+        let quedan = self.partida.jugadores_en_juego();
+        for (jugador, resultado) in self.partida.jugadores.iter_mut().zip(resultados.iter_mut()) {
+            if let Some(resultado) = resultado {
+                resultado.puede_reengancharse = resultado.perdio
+                    && resultado.pareja.is_none()
+                    && !resul_propio.chinchon
+                    && quedan >= 2
+                    && jugador.reenganches < reglas.max_reenganches;
+                jugador.puede_reengancharse = resultado.puede_reengancharse;
             }
         }
+        self.datos_mut().turnos_vencidos = 0;
+        self.partida.rondas.push(ResumenRonda {
+            corto: resul_propio.jugador,
+            chinchon: resul_propio.chinchon,
+            puntos: resultados
+                .iter()
+                .flatten()
+                .map(|r| (r.jugador, r.puntos_sumados, r.puntos_total))
+                .collect(),
+        });
+        self.partida.historial.push(Accion::Cortar {
+            jugador: resul_propio.jugador,
+            carta,
+        });
+        self.partida.marcar_eliminados(false);
         self.partida.comenzar_ronda();
+        self.partida.comprobar();
         let len = resultados.len();
         Ok(resultados
             .into_iter()
-            .flatten()
             .cycle()
             .skip(self.indice)
             .take(len)
+            .flatten()
             .collect())
     }
 
@@ -332,41 +845,80 @@ impl<'a> Jugador<'a> {
             .buscar_jugador(a)
             .ok_or_else(|| anyhow!("Ese compa no esta en la partida :/"))?;
         let expulsado = victima.votar_expulsar(id_propio, restantes);
+        let cartas = if expulsado {
+            take(&mut victima.mano)
+        } else {
+            vec![]
+        };
+        self.partida.historial.push(Accion::VotarExpulsar {
+            jugador: id_propio,
+            a,
+        });
         if expulsado {
-            let cartas = take(&mut victima.mano);
             self.partida.mazo.extend(cartas);
-            self.partida.mazo.shuffle(&mut thread_rng());
-            if self.partida.turno == turno_victima {
+            self.partida.eliminar_pareja_de(turno_victima);
+            self.partida.marcar_eliminados(true);
+            self.partida.mazo.shuffle(&mut self.partida.rng);
+            if self.partida.jugadores[self.partida.turno].perdio(&self.partida.reglas) {
                 self.partida.pasar_turno();
             }
         }
+        self.partida.comprobar();
         Ok(expulsado)
     }
 
     pub fn abandonar(&mut self) {
         let datos = self.datos_mut();
         datos.abandonar();
+        let jugador = datos.id;
         let cartas = take(&mut datos.mano);
         self.partida.mazo.extend(cartas);
-        self.partida.mazo.shuffle(&mut thread_rng());
-        if self.es_turno() {
+        self.partida.eliminar_pareja_de(self.indice);
+        self.partida.marcar_eliminados(true);
+        self.partida.mazo.shuffle(&mut self.partida.rng);
+        self.partida.historial.push(Accion::Abandonar { jugador });
+        if self.partida.jugadores[self.partida.turno].perdio(&self.partida.reglas) {
             self.partida.pasar_turno();
         }
+        self.partida.comprobar();
     }
 }
 
-fn mazo_mezclado() -> Vec<Carta> {
-    let mut cartas: Vec<Carta> = (1..=12)
-        .flat_map(|num| {
+fn mazo_mezclado(reglas: &ReglasPartida, rng: &mut ChaCha8Rng) -> Vec<Carta> {
+    let mut cartas = cartas_mazo(reglas);
+    cartas.shuffle(rng);
+    cartas
+}
+
+fn cartas_mazo(reglas: &ReglasPartida) -> Vec<Carta> {
+    let mut cartas: Vec<Carta> = reglas
+        .mazo
+        .numeros()
+        .iter()
+        .flat_map(|&num| {
             [Palo::Copa, Palo::Espada, Palo::Oro, Palo::Basto]
                 .into_iter()
                 .map(move |palo| Carta { num, palo })
         })
         .collect();
-    cartas.shuffle(&mut thread_rng());
+    if reglas.comodines {
+        cartas.extend((1..=2).map(|num| Carta {
+            num,
+            palo: Palo::Comodin,
+        }));
+    }
     cartas
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TurnoVencido {
+    Jugado {
+        levanto: Option<PilaCartas>,
+        carta: Carta,
+    },
+    Expulsado,
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub enum PilaCartas {
     Mazo,
@@ -396,13 +948,393 @@ pub enum ErrorCortar {
     DebeBajar,
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ErrorValidacion {
+    PocosJugadores(usize),
+    CartaFaltante(Carta),
+    CartaRepetida(Carta),
+    CartaDesconocida(Carta),
+    ManoMuyGrande(UserId, usize),
+    OchoCartasSinTurno(UserId),
+    ManoIncompleta(UserId, usize),
+    TurnoInexistente(usize),
+    TurnoDeEliminado(UserId),
+    InicioInexistente(usize),
+}
+
+impl Display for ErrorValidacion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PocosJugadores(n) => write!(f, "la partida tiene {n} jugadores"),
+            Self::CartaFaltante(carta) => write!(f, "falta el {}", carta.nombre()),
+            Self::CartaRepetida(carta) => write!(f, "el {} esta repetido", carta.nombre()),
+            Self::CartaDesconocida(carta) => {
+                write!(f, "el {} no es parte de este mazo", carta.nombre())
+            }
+            Self::ManoMuyGrande(id, n) => write!(f, "{id} tiene {n} cartas en la mano"),
+            Self::OchoCartasSinTurno(id) => write!(f, "{id} tiene 8 cartas sin ser su turno"),
+            Self::ManoIncompleta(id, n) => write!(f, "{id} sigue en juego con {n} cartas"),
+            Self::TurnoInexistente(i) => write!(f, "el turno es del jugador {i} que no existe"),
+            Self::TurnoDeEliminado(id) => write!(f, "el turno es de {id} que ya perdio"),
+            Self::InicioInexistente(i) => {
+                write!(f, "la proxima ronda empieza el jugador {i} que no existe")
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ResultadoFinalRonda {
     pub jugador: UserId,
     pub puntos_sumados: i16,
     pub puntos_total: i16,
     pub perdio: bool,
+    pub puede_reengancharse: bool,
+    pub pareja: Option<UserId>,
+    pub puntos_pareja: Option<i16>,
     pub chinchon: bool,
     pub juegos: Vec<Vec<Carta>>,
     pub sobrantes: Vec<Carta>,
+    pub acomodadas: Vec<CartaAcomodada>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResumenRonda {
+    pub corto: UserId,
+    pub chinchon: bool,
+    pub puntos: Vec<(UserId, i16, i16)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CartaAcomodada {
+    pub carta: Carta,
+    pub jugador: UserId,
+    pub juego: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chinchon::{jugar_turno_bot, Dificultad};
+
+    fn jugadores() -> Vec<UserId> {
+        (1..=4).map(UserId).collect()
+    }
+
+    #[test]
+    fn misma_semilla_reparte_igual() {
+        let a = Partida::empezar_con_semilla(&jugadores(), ReglasPartida::default(), 42);
+        let b = Partida::empezar_con_semilla(&jugadores(), ReglasPartida::default(), 42);
+        assert_eq!(a.mazo, b.mazo);
+        assert_eq!(a.turno, b.turno);
+        for (ja, jb) in a.jugadores.iter().zip(&b.jugadores) {
+            assert_eq!(ja.mano, jb.mano);
+        }
+    }
+
+    #[test]
+    fn otra_semilla_reparte_distinto() {
+        let a = Partida::empezar_con_semilla(&jugadores(), ReglasPartida::default(), 42);
+        let b = Partida::empezar_con_semilla(&jugadores(), ReglasPartida::default(), 43);
+        assert_ne!(a.mazo, b.mazo);
+    }
+
+    fn partida_valida() -> Partida {
+        let partida = Partida::empezar_con_semilla(&jugadores(), ReglasPartida::default(), 5);
+        assert_eq!(partida.validar(), Ok(()));
+        partida
+    }
+
+    fn sin_turno(partida: &Partida) -> usize {
+        (partida.turno + 1) % partida.jugadores.len()
+    }
+
+    #[test]
+    fn validar_detecta_cartas_repetidas() {
+        let mut partida = partida_valida();
+        let repetida = partida.jugadores[0].mano[0];
+        let faltante = std::mem::replace(&mut partida.mazo[0], repetida);
+        let errores = partida.validar().unwrap_err();
+        assert!(errores.contains(&ErrorValidacion::CartaRepetida(repetida)));
+        assert!(errores.contains(&ErrorValidacion::CartaFaltante(faltante)));
+    }
+
+    #[test]
+    fn validar_detecta_manos_de_tamano_incorrecto() {
+        let mut partida = partida_valida();
+        let indice = sin_turno(&partida);
+        let id = partida.jugadores[indice].id;
+        let carta = partida.jugadores[indice].mano.pop().unwrap();
+        partida.mazo.push(carta);
+        assert_eq!(
+            partida.validar(),
+            Err(vec![ErrorValidacion::ManoIncompleta(id, 6)])
+        );
+
+        let mut partida = partida_valida();
+        let carta = partida.mazo.pop().unwrap();
+        partida.jugadores[indice].mano.push(carta);
+        assert_eq!(
+            partida.validar(),
+            Err(vec![ErrorValidacion::OchoCartasSinTurno(id)])
+        );
+    }
+
+    #[test]
+    fn validar_detecta_turno_de_eliminado() {
+        let mut partida = partida_valida();
+        let turno = partida.turno;
+        partida.jugadores[turno].eliminado = true;
+        let id = partida.jugadores[turno].id;
+        assert_eq!(
+            partida.validar(),
+            Err(vec![ErrorValidacion::TurnoDeEliminado(id)])
+        );
+    }
+
+    /// Juega entre bots hasta que alguien se reengancha y vuelve a perder.
+    /// Devuelve false si con esta semilla no pasa.
+    fn reenganche_respeta_puntos_y_maximo(semilla: u64) -> bool {
+        let reglas = ReglasPartida {
+            max_reenganches: 1,
+            ..Default::default()
+        };
+        let mut partida = Partida::empezar_con_semilla(&jugadores()[..3], reglas, semilla);
+        let mut reenganchado = None;
+        while partida.ganador().is_none() && partida.historial.len() < 20000 {
+            let turno = partida.get_turno();
+            let jugada = jugar_turno_bot(&mut partida.jugador(turno).unwrap(), Dificultad::Dificil);
+            for res in jugada.corte.iter().flatten() {
+                if res.perdio && Some(res.jugador) == reenganchado {
+                    assert!(!res.puede_reengancharse);
+                    assert!(partida.reenganchar(res.jugador).is_err());
+                    return true;
+                }
+                if res.puede_reengancharse && reenganchado.is_none() {
+                    let peor = partida
+                        .jugadores
+                        .iter()
+                        .filter(|j| !j.perdio(&reglas))
+                        .map(|j| j.puntos)
+                        .max()
+                        .unwrap();
+                    assert_eq!(partida.reenganchar(res.jugador).unwrap(), peor);
+                    assert_eq!(partida.get_puntos()[&res.jugador], peor);
+                    assert!(partida.esta_en_juego(res.jugador));
+                    assert_eq!(partida.validar(), Ok(()));
+                    assert!(partida.reenganchar(res.jugador).is_err());
+                    reenganchado = Some(res.jugador);
+                }
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn reenganche_vuelve_con_los_puntos_del_peor_hasta_el_maximo() {
+        assert!((0..20).any(reenganche_respeta_puntos_y_maximo));
+    }
+
+    /// Reparte manos fijas y deja el resto de las cartas en el mazo
+    fn dar_manos(partida: &mut Partida, manos: Vec<Vec<Carta>>) {
+        let mut resto: Vec<Carta> = partida.mazo.drain(..).collect();
+        resto.append(&mut partida.descartes);
+        for jugador in &mut partida.jugadores {
+            resto.append(&mut jugador.mano);
+        }
+        for (jugador, mut mano) in partida.jugadores.iter_mut().zip(manos) {
+            for carta in &mano {
+                resto.remove(resto.iter().position(|c| c == carta).unwrap());
+            }
+            mano.sort_unstable();
+            jugador.mano = mano;
+        }
+        partida.mazo = resto;
+    }
+
+    fn cartas(palo: Palo, numeros: &[u8]) -> Vec<Carta> {
+        numeros.iter().map(|&num| Carta { num, palo }).collect()
+    }
+
+    /// El primero corta con 1 punto. Su pareja suma 46, los rivales 56 y 45.
+    fn cortar_en_parejas(reglas: ReglasPartida) -> (Partida, Vec<ResultadoFinalRonda>) {
+        let reglas = ReglasPartida {
+            parejas: true,
+            ..reglas
+        };
+        let mut partida = Partida::empezar_con_semilla(&jugadores(), reglas, 3);
+        let manos = vec![
+            [
+                cartas(Palo::Copa, &[1, 2, 3]),
+                cartas(Palo::Oro, &[1, 4]),
+                cartas(Palo::Espada, &[4, 12]),
+                cartas(Palo::Basto, &[4]),
+            ]
+            .concat(),
+            [
+                cartas(Palo::Oro, &[7]),
+                cartas(Palo::Espada, &[2, 9]),
+                cartas(Palo::Basto, &[6, 11]),
+                cartas(Palo::Copa, &[9, 12]),
+            ]
+            .concat(),
+            [
+                cartas(Palo::Oro, &[5, 8]),
+                cartas(Palo::Copa, &[7, 11]),
+                cartas(Palo::Espada, &[10]),
+                cartas(Palo::Basto, &[2, 3]),
+            ]
+            .concat(),
+            [
+                cartas(Palo::Oro, &[6]),
+                cartas(Palo::Espada, &[1, 3, 8]),
+                cartas(Palo::Basto, &[10, 12]),
+                cartas(Palo::Copa, &[5]),
+            ]
+            .concat(),
+        ];
+        dar_manos(&mut partida, manos);
+        partida.turno = 0;
+        assert_eq!(partida.validar(), Ok(()));
+        let resultados = partida
+            .jugador(UserId(1))
+            .unwrap()
+            .cortar(Some(Carta {
+                num: 12,
+                palo: Palo::Espada,
+            }))
+            .unwrap();
+        (partida, resultados)
+    }
+
+    /// Corta el primero con una escalera de siete de copas, sin tirar nada
+    fn cortar_con_escalera(
+        reglas: ReglasPartida,
+        escalera: Vec<Carta>,
+    ) -> Vec<ResultadoFinalRonda> {
+        let mut partida = Partida::empezar_con_semilla(&jugadores()[..2], reglas, 3);
+        let rival = [
+            cartas(Palo::Oro, &[2, 5, 9, 11]),
+            cartas(Palo::Basto, &[1, 6, 12]),
+        ]
+        .concat();
+        dar_manos(&mut partida, vec![escalera, rival]);
+        partida.turno = 0;
+        partida.jugador(UserId(1)).unwrap().cortar(None).unwrap()
+    }
+
+    #[test]
+    fn chinchon_sin_comodin_gana_la_ronda() {
+        let resultados = cortar_con_escalera(
+            ReglasPartida::default(),
+            cartas(Palo::Copa, &[3, 4, 5, 6, 7, 8, 9]),
+        );
+        let propio = resultado_de(&resultados, 1);
+        assert!(propio.chinchon);
+        assert!(resultado_de(&resultados, 2).perdio);
+    }
+
+    #[test]
+    fn escalera_con_comodin_no_es_chinchon() {
+        let reglas = ReglasPartida {
+            comodines: true,
+            ..Default::default()
+        };
+        let mut escalera = cartas(Palo::Copa, &[3, 4, 5, 7, 8, 9]);
+        escalera.push(Carta {
+            num: 1,
+            palo: Palo::Comodin,
+        });
+        let resultados = cortar_con_escalera(reglas, escalera);
+        let propio = resultado_de(&resultados, 1);
+        assert!(!propio.chinchon);
+        assert_eq!(propio.puntos_sumados, reglas.puntos_cortar_cero);
+        assert!(!resultado_de(&resultados, 2).perdio);
+    }
+
+    fn resultado_de(resultados: &[ResultadoFinalRonda], id: u64) -> &ResultadoFinalRonda {
+        resultados.iter().find(|r| r.jugador == UserId(id)).unwrap()
+    }
+
+    #[test]
+    fn parejas_suman_sus_puntos() {
+        let (partida, resultados) = cortar_en_parejas(ReglasPartida {
+            limite_puntos: 150,
+            ..Default::default()
+        });
+        let rival = resultado_de(&resultados, 2);
+        assert_eq!(rival.pareja, Some(UserId(4)));
+        assert_eq!(rival.puntos_pareja, Some(56 + 45));
+        assert_eq!(resultado_de(&resultados, 1).puntos_pareja, Some(1 + 46));
+        assert!(resultados.iter().all(|r| !r.perdio));
+        assert_eq!(partida.get_puntos_pareja(UserId(4)), Some(101));
+        assert_eq!(partida.ganador(), None);
+    }
+
+    #[test]
+    fn parejas_con_puntaje_maximo() {
+        let (_, resultados) = cortar_en_parejas(ReglasPartida {
+            puntaje_parejas: PuntajeParejas::Maximo,
+            ..Default::default()
+        });
+        assert_eq!(resultado_de(&resultados, 2).puntos_pareja, Some(56));
+        assert_eq!(resultado_de(&resultados, 3).puntos_pareja, Some(46));
+        assert!(resultados.iter().all(|r| !r.perdio));
+    }
+
+    #[test]
+    fn pareja_eliminada_termina_la_partida() {
+        let (partida, resultados) = cortar_en_parejas(ReglasPartida::default());
+        assert!(resultado_de(&resultados, 2).perdio);
+        assert!(resultado_de(&resultados, 4).perdio);
+        assert!(!resultado_de(&resultados, 3).perdio);
+        assert_eq!(partida.ganador(), Some(UserId(1)));
+        assert_eq!(partida.get_pareja(UserId(1)), Some(UserId(3)));
+        assert_eq!(
+            partida.posiciones(),
+            vec![vec![UserId(1), UserId(3)], vec![UserId(2), UserId(4)]]
+        );
+    }
+
+    fn terminar_ronda(partida: &mut Partida, perdedores: &[UserId]) {
+        partida.rondas.push(ResumenRonda {
+            corto: UserId(1),
+            chinchon: false,
+            puntos: vec![],
+        });
+        for jugador in &mut partida.jugadores {
+            if perdedores.contains(&jugador.id) {
+                jugador.puntos = partida.reglas.limite_puntos + 1;
+            }
+        }
+        partida.marcar_eliminados(false);
+    }
+
+    #[test]
+    fn posiciones_empatan_a_los_que_pierden_en_la_misma_ronda() {
+        let mut partida = Partida::empezar_con_semilla(&jugadores(), ReglasPartida::default(), 1);
+        terminar_ronda(&mut partida, &[UserId(3), UserId(4)]);
+        terminar_ronda(&mut partida, &[UserId(2)]);
+        assert_eq!(
+            partida.posiciones(),
+            vec![vec![UserId(1)], vec![UserId(2)], vec![UserId(3), UserId(4)]]
+        );
+    }
+
+    #[test]
+    fn posiciones_ponen_a_quien_abandona_entre_rondas() {
+        let mut partida = Partida::empezar_con_semilla(&jugadores(), ReglasPartida::default(), 1);
+        terminar_ronda(&mut partida, &[UserId(4)]);
+        partida.jugador(UserId(2)).unwrap().abandonar();
+        terminar_ronda(&mut partida, &[UserId(3)]);
+        assert_eq!(
+            partida.posiciones(),
+            vec![
+                vec![UserId(1)],
+                vec![UserId(3)],
+                vec![UserId(2)],
+                vec![UserId(4)]
+            ]
+        );
+    }
 }
@@ -1,25 +1,27 @@
 use crate::{
+    chinchon::{Dificultad, PuntajeParejas, ReglasPartida, TipoMazo, MAX_BOTS},
     config_servers::ConfigServers,
-    crear_hilo::crear_hilo_partida,
-    lista_partidas::{ErrorEmpezarPartida, ListaPartidas, RespuestaEmpezarPartida},
+    estadisticas::Estadisticas,
+    eventos::{empezar_partida, jugar_turnos_bots},
+    lista_partidas::{ErrorEmpezarPartida, Invitacion, ListaPartidas, RespuestaEmpezarPartida},
     mensajes::mensaje_invitacion,
-    opciones_comandos::{get_opcion, get_opcion_o_default},
+    opciones_comandos::{get_opcion, get_opcion_o_default, get_opcion_o_none},
 };
 use anyhow::{anyhow, Result};
 use serenity::{
     client::Context,
     model::{
-        id::{MessageId, UserId},
-        interactions::application_command::ApplicationCommandInteraction,
-        user::User,
+        application::interaction::application_command::ApplicationCommandInteraction, user::User,
     },
 };
+use std::sync::Arc;
 
 pub async fn comando_invitacion(
     ctx: &Context,
     inter: &ApplicationCommandInteraction,
-    partidas: &ListaPartidas,
+    partidas: &Arc<ListaPartidas>,
     config_servers: &ConfigServers,
+    estadisticas: &mut Estadisticas,
 ) -> Result<()> {
     match inter.data.name.as_str() {
         "chinchon" => {
@@ -34,15 +36,58 @@ pub async fn comando_invitacion(
                 ));
             }
             let privada = get_opcion_o_default("privada", inter, false)?;
-            let max_jugadores = get_opcion_o_default("jugadores", inter, 2)?.clamp(2, 4);
-            let (contenido, acciones) = mensaje_invitacion(
-                &ctx.http,
+            let mut max_jugadores = get_opcion_o_default("jugadores", inter, 2)?.clamp(2, 4);
+            let mut reglas = ReglasPartida::default();
+            let pareja = get_opcion_o_none::<User>("pareja", inter)?
+                .map(|u| u.id)
+                .filter(|id| *id != inter.user.id);
+            let parejas = get_opcion_o_none::<String>("parejas", inter)?;
+            if parejas.is_some() || pareja.is_some() {
+                reglas.parejas = true;
+                reglas.puntaje_parejas = match parejas.as_deref() {
+                    Some("maximo") => PuntajeParejas::Maximo,
+                    _ => PuntajeParejas::Suma,
+                };
+                max_jugadores = 4;
+            }
+            if let Some(limite) = get_opcion_o_none::<i64>("limite", inter)? {
+                reglas.limite_puntos = limite.clamp(20, 500) as i16;
+            }
+            if let Some(corta_con) = get_opcion_o_none::<i64>("corta_con", inter)? {
+                reglas.max_puntos_cortar = corta_con.clamp(0, 10) as i16;
+            }
+            if let Some(puntos_comodin) = get_opcion_o_none::<i64>("comodines", inter)? {
+                reglas.comodines = true;
+                reglas.puntos_comodin = puntos_comodin.clamp(0, 50) as i16;
+            }
+            if get_opcion_o_default("reenganche", inter, false)? && !reglas.parejas {
+                reglas.max_reenganches = 1;
+            }
+            reglas.acomodar = get_opcion_o_default("acomodar", inter, false)?;
+            reglas.segundos_turno = get_opcion_o_none::<i64>("tiempo", inter)?
+                .map(|segundos| segundos.clamp(30, 3600) as u32);
+            if get_opcion_o_default::<i64>("cartas", inter, 48)? == 40 {
+                reglas.mazo = TipoMazo::De40;
+            }
+            let dificultad =
+                match get_opcion_o_default("dificultad", inter, "facil".to_owned())?.as_str() {
+                    "dificil" => Dificultad::Dificil,
+                    _ => Dificultad::Facil,
+                };
+            let cant_bots = get_opcion_o_default("bots", inter, 0)?
+                .clamp(0, MAX_BOTS as i64)
+                .min(max_jugadores - 1 - pareja.is_some() as i64);
+            let bots = vec![dificultad; cant_bots as usize];
+            let invitacion = Invitacion::new(
                 inter.user.id,
-                &[inter.user.id],
-                max_jugadores as u64,
-                privada,
-            )
-            .await;
+                if privada { Some(vec![]) } else { None },
+                max_jugadores as usize,
+                reglas,
+                bots,
+                pareja,
+            );
+            let (contenido, acciones) =
+                mensaje_invitacion(&ctx.http, inter.user.id, &invitacion).await;
             inter
                 .create_interaction_response(&ctx.http, |resp| {
                     resp.interaction_response_data(|msg| {
@@ -54,17 +99,24 @@ pub async fn comando_invitacion(
                 .unwrap();
             let mensaje = inter.get_interaction_response(&ctx.http).await.unwrap();
             let inv_vieja = partidas
-                .crear_invitacion(
-                    inter.channel_id,
-                    inter.user.id,
-                    mensaje.id,
-                    if privada { Some(vec![]) } else { None },
-                    max_jugadores as usize,
-                )
+                .crear_invitacion(inter.channel_id, inter.user.id, mensaje.id, invitacion)
                 .await;
             if let Some((canal, mensaje)) = inv_vieja {
                 ctx.http.delete_message(canal.0, mensaje.0).await.unwrap();
             }
+            if cant_bots + 1 >= max_jugadores {
+                let RespuestaEmpezarPartida { canal, .. } =
+                    empezar_partida(&ctx.http, partidas, inter.channel_id, inter.user.id)
+                        .await
+                        .map_err(|_| anyhow!("Algo salio mal bb :( no se pudo crear el hilo"))?;
+                jugar_turnos_bots(
+                    &ctx.http,
+                    estadisticas,
+                    partidas,
+                    inter.guild_id.unwrap(),
+                    canal,
+                );
+            }
         }
         "invitar" => {
             let invitado: User = get_opcion("a", inter)?;
@@ -79,16 +131,11 @@ pub async fn comando_invitacion(
                 .unwrap();
         }
         "empezar" => {
-            let http = ctx.http.clone();
-            let canal = inter.channel_id;
-            let RespuestaEmpezarPartida { mensaje_invi, .. } = partidas
-                .empezar_partida(
-                    inter.channel_id,
-                    inter.user.id,
-                    |mensaje: MessageId, jugadores: Vec<UserId>, comienza: UserId| async move {
-                        crear_hilo_partida(&http, canal, mensaje, &jugadores, comienza).await
-                    },
-                )
+            let RespuestaEmpezarPartida {
+                mensaje_invi,
+                canal,
+                ..
+            } = empezar_partida(&ctx.http, partidas, inter.channel_id, inter.user.id)
                 .await
                 .map_err(|e| match e {
                     ErrorEmpezarPartida::InvitacionNoExiste => {
@@ -114,6 +161,13 @@ pub async fn comando_invitacion(
                 })
                 .await
                 .unwrap();
+            jugar_turnos_bots(
+                &ctx.http,
+                estadisticas,
+                partidas,
+                inter.guild_id.unwrap(),
+                canal,
+            );
         }
         _ => return Err(anyhow!("chica q dices")),
     }
@@ -1,34 +1,35 @@
 mod invitaciones;
 mod partida;
+mod torneos;
 
-use self::{invitaciones::comando_invitacion, partida::comando_partida};
+use self::{invitaciones::comando_invitacion, partida::comando_partida, torneos::comando_torneo};
 use crate::{
-    config_servers::ConfigServers,
+    config_servers::{ConfigRecordatorios, ConfigServers},
     errores::ErrorGenerico,
     estadisticas::Estadisticas,
-    eventos::fin_partida,
+    eventos::{fin_partida, jugar_turnos_bots},
     lista_partidas::ListaPartidas,
-    mensajes::mensaje_estadisticas,
+    mensajes::{mensaje_espectador, mensaje_estadisticas, mensaje_historial, mensaje_ranking},
     opciones_comandos::{get_opcion, get_opcion_o_default},
 };
 use anyhow::{anyhow, Result};
 use serenity::{
     client::Context,
     model::{
-        channel::PartialChannel,
-        interactions::{
+        application::interaction::{
             application_command::ApplicationCommandInteraction, InteractionResponseType,
         },
+        channel::{AttachmentType, PartialChannel},
         user::User,
     },
 };
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 use tokio::time::sleep;
 
 pub async fn procesar_comando(
     ctx: &Context,
     inter: &ApplicationCommandInteraction,
-    partidas: &ListaPartidas,
+    partidas: &Arc<ListaPartidas>,
     config_servers: &mut ConfigServers,
     estadisticas: &mut Estadisticas,
 ) -> Result<()> {
@@ -60,6 +61,35 @@ pub async fn procesar_comando(
                 .await
                 .unwrap();
         }
+        "recordatorios" => {
+            let minutos: i64 = get_opcion("minutos", inter)?;
+            let md: bool = get_opcion_o_default("md", inter, false)?;
+            let recordatorios = (minutos > 0).then_some(ConfigRecordatorios {
+                minutos: minutos as u64,
+                md,
+            });
+            config_servers
+                .set_recordatorios(inter.guild_id.unwrap(), recordatorios)
+                .await
+                .error_generico()?;
+            let contenido = match recordatorios {
+                Some(r) if r.md => format!(
+                    "Listo ^^ Voy a avisar cada {} minutos a quien no juega su turno, tambien por md",
+                    r.minutos
+                ),
+                Some(r) => format!(
+                    "Listo ^^ Voy a avisar cada {} minutos a quien no juega su turno",
+                    r.minutos
+                ),
+                None => "Listo, ya no voy a recordar los turnos".to_owned(),
+            };
+            inter
+                .create_interaction_response(&ctx.http, |resp| {
+                    resp.interaction_response_data(|msg| msg.content(contenido).ephemeral(true))
+                })
+                .await
+                .unwrap();
+        }
         "stats" => {
             let jugador: User = get_opcion_o_default("jugador", inter, inter.user.clone())?;
             let embed = mensaje_estadisticas(&jugador, inter.guild_id, estadisticas).await?;
@@ -70,26 +100,118 @@ pub async fn procesar_comando(
                 .await
                 .unwrap();
         }
+        "historial" => {
+            let jugador: User = get_opcion_o_default("jugador", inter, inter.user.clone())?;
+            let (embed, acciones) = mensaje_historial(&ctx.http, &jugador, estadisticas, 0).await?;
+            inter
+                .create_interaction_response(&ctx.http, |resp| {
+                    resp.interaction_response_data(|msg| {
+                        msg.set_embed(embed)
+                            .components(|comps| comps.set_action_rows(acciones))
+                    })
+                })
+                .await
+                .unwrap();
+        }
+        "ranking" => {
+            let criterio = get_opcion_o_default("por", inter, "victorias".to_owned())?.parse()?;
+            let (embed, acciones) = mensaje_ranking(
+                &ctx.http,
+                inter.guild_id.unwrap(),
+                estadisticas,
+                criterio,
+                0,
+            )
+            .await?;
+            inter
+                .create_interaction_response(&ctx.http, |resp| {
+                    resp.interaction_response_data(|msg| {
+                        msg.set_embed(embed)
+                            .components(|comps| comps.set_action_rows(acciones))
+                    })
+                })
+                .await
+                .unwrap();
+        }
+        "exportar" => {
+            let (notacion, contenido) = match partidas.get_partida(inter.channel_id).await {
+                Some(partida) => {
+                    let partida = partida.lock().await;
+                    if partida.ganador().is_some() {
+                        (partida.notacion(), "Aqui tienes la partida completa ^^")
+                    } else {
+                        (
+                            partida.notacion_en_curso(),
+                            "Aqui tienes lo que va de la partida ^^ Sin las cartas de nadie, que todavia se esta jugando",
+                        )
+                    }
+                }
+                None => (
+                    estadisticas
+                        .get_notacion(inter.channel_id)
+                        .await?
+                        .ok_or_else(|| anyhow!("No encuentro ninguna partida en este canal :("))?,
+                    "Aqui tienes la partida completa ^^",
+                ),
+            };
+            inter
+                .create_interaction_response(&ctx.http, |resp| {
+                    resp.interaction_response_data(|msg| {
+                        msg.content(contenido).add_file(AttachmentType::Bytes {
+                            data: notacion.into_bytes().into(),
+                            filename: format!("chinchon-{}.txt", inter.channel_id),
+                        })
+                    })
+                })
+                .await
+                .unwrap();
+        }
+        "ver" => {
+            let partida = partidas
+                .get_partida(inter.channel_id)
+                .await
+                .ok_or_else(|| anyhow!("No hay ninguna partida en este canal :("))?;
+            let partida = partida.lock().await.clone();
+            let mensaje = mensaje_espectador(&ctx.http, &partida).await;
+            inter
+                .create_interaction_response(&ctx.http, |resp| {
+                    resp.interaction_response_data(|msg| msg.ephemeral(true).content(mensaje))
+                })
+                .await
+                .unwrap();
+        }
         "chinchon" | "invitar" | "empezar" => {
-            comando_invitacion(ctx, inter, partidas, config_servers).await?
+            comando_invitacion(ctx, inter, partidas, config_servers, estadisticas).await?
         }
+        "torneo" => comando_torneo(ctx, inter, partidas, config_servers).await?,
         "jugar" | "puntos" | "cartas" | "salir" | "kick" => {
             let partida = partidas
                 .get_partida(inter.channel_id)
                 .await
                 .ok_or_else(|| anyhow!("No hay ninguna partida en este canal :("))?;
             comando_partida(ctx, inter, partida.clone(), estadisticas).await?;
-            if let Some(ganador) = partida.lock().await.ganador() {
-                partidas.terminar_partida(inter.channel_id).await.unwrap();
-                fin_partida(
+            let ganador = partida.lock().await.ganador();
+            if ganador.is_some() {
+                if let Ok(partida) = partidas.terminar_partida(inter.channel_id).await {
+                    fin_partida(
+                        &ctx.http,
+                        estadisticas,
+                        partidas,
+                        inter.guild_id.unwrap(),
+                        inter.channel_id,
+                        &partida,
+                    )
+                    .await;
+                }
+            } else {
+                jugar_turnos_bots(
                     &ctx.http,
                     estadisticas,
+                    partidas,
                     inter.guild_id.unwrap(),
                     inter.channel_id,
-                    ganador,
-                )
-                .await;
-            };
+                );
+            }
         }
         _ => return Err(anyhow!("nani?")),
     }
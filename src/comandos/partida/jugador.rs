@@ -1,14 +1,16 @@
 use crate::{
     chinchon::Jugador,
     estadisticas::Estadisticas,
-    eventos::abandono,
+    eventos::{abandono, expulsion},
     mensajes::{mensaje_cartas, mensaje_jugar},
     opciones_comandos::get_opcion,
 };
 use anyhow::{anyhow, Result};
 use serenity::{
     client::Context,
-    model::{interactions::application_command::ApplicationCommandInteraction, user::User},
+    model::{
+        application::interaction::application_command::ApplicationCommandInteraction, user::User,
+    },
 };
 
 pub async fn comando_jugador(
@@ -46,6 +48,7 @@ pub async fn comando_jugador(
         }
         "salir" => {
             let turno_antes = jugador.partida.get_turno();
+            let pareja = jugador.partida.get_pareja(inter.user.id);
             jugador.abandonar();
             inter
                 .create_interaction_response(&ctx.http, |resp| {
@@ -66,6 +69,7 @@ pub async fn comando_jugador(
                 inter.guild_id.unwrap(),
                 inter.channel_id,
                 inter.user.id,
+                pareja,
                 cambio_turno,
             )
             .await;
@@ -95,12 +99,13 @@ pub async fn comando_jugador(
                 } else {
                     Some(jugador.partida.get_turno()).filter(|t| *t != turno_antes)
                 };
-                abandono(
+                expulsion(
                     &ctx.http,
                     estadisticas,
                     inter.guild_id.unwrap(),
                     inter.channel_id,
                     victima.id,
+                    jugador.partida.get_pareja(victima.id),
                     cambio_turno,
                 )
                 .await;
@@ -1,10 +1,11 @@
 mod info;
 mod jugador;
 
-use crate::{chinchon::Partida, estadisticas::Estadisticas};
+use crate::{chinchon::Partida, estadisticas::Estadisticas, mensajes::nombre_jugador};
 use anyhow::{anyhow, Result};
 use serenity::{
-    client::Context, model::interactions::application_command::ApplicationCommandInteraction,
+    client::Context,
+    model::application::interaction::application_command::ApplicationCommandInteraction,
 };
 use std::{fmt::Write, sync::Arc};
 use tokio::sync::Mutex;
@@ -26,23 +27,49 @@ pub async fn comando_partida(
             comando_jugador(ctx, inter, &mut jugador, estadisticas).await?;
         }
         "puntos" => {
-            let puntos: Vec<_> = {
+            let (puntos, reglas): (Vec<_>, _) = {
                 let partida = partida.lock().await;
-                partida.get_puntos().into_iter().collect()
+                (
+                    partida
+                        .get_puntos()
+                        .into_iter()
+                        .map(|(id, puntos)| {
+                            (
+                                id,
+                                puntos,
+                                partida.get_pareja(id),
+                                partida.get_puntos_pareja(id),
+                            )
+                        })
+                        .collect(),
+                    partida.get_reglas(),
+                )
             };
-            let mut tabla = String::new();
-            for (user_id, puntos) in puntos {
+            let mut tabla = format!("Se juega a **{}**\n", reglas.limite_puntos);
+            for (user_id, puntos, _, _) in &puntos {
                 let _ = writeln!(
                     tabla,
                     "**{}**: {}",
-                    user_id
-                        .to_user(&ctx.http)
-                        .await
-                        .map(|u| u.name)
-                        .unwrap_or_else(|_| "?".to_owned()),
+                    nombre_jugador(&ctx.http, *user_id).await,
                     puntos
                 );
             }
+            if reglas.parejas {
+                tabla += "\n";
+                for (user_id, _, pareja, puntos_pareja) in &puntos {
+                    if let (Some(pareja), Some(puntos_pareja)) = (pareja, puntos_pareja) {
+                        if user_id < pareja {
+                            let _ = writeln!(
+                                tabla,
+                                "**{} y {}**: {}",
+                                nombre_jugador(&ctx.http, *user_id).await,
+                                nombre_jugador(&ctx.http, *pareja).await,
+                                puntos_pareja
+                            );
+                        }
+                    }
+                }
+            }
             inter
                 .create_interaction_response(&ctx.http, |resp| {
                     resp.interaction_response_data(|msg| {
@@ -0,0 +1,68 @@
+use crate::{
+    chinchon::ReglasPartida,
+    config_servers::ConfigServers,
+    lista_partidas::ListaPartidas,
+    lista_torneos::Torneo,
+    mensajes::mensaje_torneo,
+    opciones_comandos::{get_opcion, get_opcion_o_default, get_opcion_o_none},
+};
+use anyhow::{anyhow, Result};
+use serenity::{
+    client::Context,
+    model::{
+        application::interaction::application_command::ApplicationCommandInteraction, id::MessageId,
+    },
+};
+
+pub async fn comando_torneo(
+    ctx: &Context,
+    inter: &ApplicationCommandInteraction,
+    partidas: &ListaPartidas,
+    config_servers: &ConfigServers,
+) -> Result<()> {
+    if !config_servers
+        .puede_crear_partidas(inter.guild_id.unwrap(), inter.channel_id)
+        .await
+        .unwrap()
+    {
+        return Err(anyhow!(
+            "No se pueden crear partidas en este canal :(\n\
+            Un admin puede elegir el canal para crear partidas usando **/canal #nombre**"
+        ));
+    }
+    if partidas.torneos.get(inter.channel_id).await.is_some() {
+        return Err(anyhow!(
+            "Ya hay un torneo en este canal, esperen a que termine uwu"
+        ));
+    }
+    let nombre: String = get_opcion("nombre", inter)?;
+    let jugadores_por_mesa = get_opcion_o_default("jugadores", inter, 2)?.clamp(2, 4);
+    let mut reglas = ReglasPartida::default();
+    if let Some(limite) = get_opcion_o_none::<i64>("limite", inter)? {
+        reglas.limite_puntos = limite.clamp(20, 500) as i16;
+    }
+    let mut torneo = Torneo::new(
+        nombre.chars().take(80).collect(),
+        inter.user.id,
+        MessageId(0),
+        jugadores_por_mesa as usize,
+        reglas,
+    );
+    let (contenido, acciones) = mensaje_torneo(&ctx.http, &torneo).await;
+    inter
+        .create_interaction_response(&ctx.http, |resp| {
+            resp.interaction_response_data(|msg| {
+                msg.content(contenido)
+                    .components(|comps| comps.set_action_rows(acciones))
+            })
+        })
+        .await
+        .unwrap();
+    let mensaje = inter.get_interaction_response(&ctx.http).await.unwrap();
+    torneo.mensaje = mensaje.id;
+    if let Err(err) = partidas.torneos.crear(inter.channel_id, torneo).await {
+        let _ = mensaje.delete(&ctx.http).await;
+        return Err(err);
+    }
+    Ok(())
+}
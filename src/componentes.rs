@@ -1,26 +1,31 @@
 use crate::{
     chinchon::{Carta, Partida, PilaCartas},
-    crear_hilo::crear_hilo_partida,
     errores::ErrorGenerico,
     estadisticas::Estadisticas,
-    eventos::{fin_partida, perdio},
-    lista_partidas::ListaPartidas,
-    mensajes::{mensaje_cartas, mensaje_cortar, mensaje_invitacion, mensaje_jugar},
+    eventos::{empezar_partida, empezar_ronda_torneo, fin_ronda, jugar_turnos_bots, reenganche},
+    lista_partidas::{ListaPartidas, RespuestaEmpezarPartida},
+    mensajes::{
+        mensaje_cartas, mensaje_cortar, mensaje_espectador, mensaje_historial, mensaje_invitacion,
+        mensaje_jugar, mensaje_ranking, mensaje_tiro, mensaje_torneo,
+    },
 };
 use anyhow::{anyhow, Result};
 use serenity::{
     client::Context,
     model::{
-        id::{MessageId, UserId},
-        interactions::{message_component::MessageComponentInteraction, InteractionResponseType},
+        application::interaction::{
+            message_component::MessageComponentInteraction, InteractionResponseType,
+        },
+        id::UserId,
     },
 };
+use std::sync::Arc;
 use tokio::sync::MutexGuard;
 
 pub async fn inter_componente(
     ctx: &Context,
     inter: &mut MessageComponentInteraction,
-    partidas: &ListaPartidas,
+    partidas: &Arc<ListaPartidas>,
     estadisticas: &mut Estadisticas,
 ) -> Result<()> {
     match inter.data.custom_id.as_str() {
@@ -41,14 +46,7 @@ pub async fn inter_componente(
                     inter.user.id,
                 )
                 .await?;
-            let (contenido, acciones) = mensaje_invitacion(
-                &ctx.http,
-                creador_invi,
-                &partida.jugadores(),
-                partida.max_jugadores as u64,
-                partida.privada(),
-            )
-            .await;
+            let (contenido, acciones) = mensaje_invitacion(&ctx.http, creador_invi, &partida).await;
             inter
                 .create_interaction_response(&ctx.http, |resp| {
                     resp.kind(InteractionResponseType::UpdateMessage)
@@ -60,18 +58,17 @@ pub async fn inter_componente(
                 .await
                 .unwrap();
             if partida.llena() {
-                let http = ctx.http.clone();
-                let canal = inter.channel_id;
-                partidas
-                    .empezar_partida(
-                        canal,
-                        creador_invi,
-                        |mensaje: MessageId, jugadores: Vec<UserId>, comienza: UserId| async move {
-                            crear_hilo_partida(&http, canal, mensaje, &jugadores, comienza).await
-                        },
-                    )
-                    .await
-                    .error_generico()?;
+                let RespuestaEmpezarPartida { canal, .. } =
+                    empezar_partida(&ctx.http, partidas, inter.channel_id, creador_invi)
+                        .await
+                        .error_generico()?;
+                jugar_turnos_bots(
+                    &ctx.http,
+                    estadisticas,
+                    partidas,
+                    inter.guild_id.unwrap(),
+                    canal,
+                );
             }
         }
         comando if comando.starts_with("jugar") => {
@@ -163,6 +160,13 @@ pub async fn inter_componente(
                     .error_generico()?;
             } else {
                 bajar(ctx, inter, partida, carta_selec).await?;
+                jugar_turnos_bots(
+                    &ctx.http,
+                    estadisticas,
+                    partidas,
+                    inter.guild_id.unwrap(),
+                    inter.channel_id,
+                );
             }
         }
         comando if comando.starts_with("bajar") => {
@@ -172,6 +176,13 @@ pub async fn inter_componente(
                 .await
                 .ok_or_else(|| anyhow!("No encuentro esta partida :c"))?;
             bajar(ctx, inter, partida.lock().await, carta).await?;
+            jugar_turnos_bots(
+                &ctx.http,
+                estadisticas,
+                partidas,
+                inter.guild_id.unwrap(),
+                inter.channel_id,
+            );
         }
         comando if comando.starts_with("cortar") => {
             let guild_id = inter.guild_id.unwrap();
@@ -191,8 +202,8 @@ pub async fn inter_componente(
             let (mensaje, acciones) = mensaje_cortar(
                 &ctx.http,
                 &resultados,
-                &inter.user,
-                (ganador.is_none()).then(|| turno),
+                &inter.user.name,
+                (ganador.is_none()).then_some(turno),
             )
             .await;
             inter
@@ -204,22 +215,150 @@ pub async fn inter_componente(
                 })
                 .await
                 .error_generico()?;
-            for res in &resultados {
-                if res.perdio {
-                    perdio(estadisticas, guild_id, res.jugador).await;
+            fin_ronda(
+                &ctx.http,
+                estadisticas,
+                partidas,
+                guild_id,
+                inter.channel_id,
+                &resultados,
+                ganador,
+            )
+            .await;
+            jugar_turnos_bots(
+                &ctx.http,
+                estadisticas,
+                partidas,
+                guild_id,
+                inter.channel_id,
+            );
+        }
+        "reenganchar" => {
+            let partida = partidas
+                .get_partida(inter.channel_id)
+                .await
+                .ok_or_else(|| anyhow!("No encuentro esta partida :c"))?;
+            let puntos = partida.lock().await.reenganchar(inter.user.id)?;
+            inter
+                .create_interaction_response(&ctx.http, |resp| {
+                    resp.interaction_response_data(|msg| {
+                        msg.content(format!(
+                            "**{}** se reengancho con **{}** puntos 😳",
+                            inter.user.name, puntos
+                        ))
+                    })
+                })
+                .await
+                .error_generico()?;
+            reenganche(estadisticas, inter.guild_id.unwrap(), inter.user.id).await;
+        }
+        comando if comando.starts_with("historial") => {
+            let mut partes = comando.split(' ').skip(1);
+            let jugador: UserId = partes.next().error_generico()?.parse().error_generico()?;
+            let pagina = partes.next().error_generico()?.parse().error_generico()?;
+            let jugador = jugador.to_user(&ctx.http).await.error_generico()?;
+            let (embed, acciones) =
+                mensaje_historial(&ctx.http, &jugador, estadisticas, pagina).await?;
+            inter
+                .create_interaction_response(&ctx.http, |resp| {
+                    resp.kind(InteractionResponseType::UpdateMessage)
+                        .interaction_response_data(|msg| {
+                            msg.set_embed(embed)
+                                .components(|comps| comps.set_action_rows(acciones))
+                        })
+                })
+                .await
+                .error_generico()?;
+        }
+        comando if comando.starts_with("ranking") => {
+            let mut partes = comando.split(' ').skip(1);
+            let criterio = partes.next().error_generico()?.parse()?;
+            let pagina = partes.next().error_generico()?.parse().error_generico()?;
+            let (embed, acciones) = mensaje_ranking(
+                &ctx.http,
+                inter.guild_id.unwrap(),
+                estadisticas,
+                criterio,
+                pagina,
+            )
+            .await?;
+            inter
+                .create_interaction_response(&ctx.http, |resp| {
+                    resp.kind(InteractionResponseType::UpdateMessage)
+                        .interaction_response_data(|msg| {
+                            msg.set_embed(embed)
+                                .components(|comps| comps.set_action_rows(acciones))
+                        })
+                })
+                .await
+                .error_generico()?;
+        }
+        "mirar" => {
+            let partida = partidas
+                .get_partida(inter.channel_id)
+                .await
+                .ok_or_else(|| anyhow!("Esta partida ya termino :c"))?;
+            let partida = partida.lock().await.clone();
+            let mensaje = mensaje_espectador(&ctx.http, &partida).await;
+            inter
+                .create_interaction_response(&ctx.http, |resp| {
+                    resp.interaction_response_data(|msg| msg.ephemeral(true).content(mensaje))
+                })
+                .await
+                .error_generico()?;
+        }
+        "anotarse torneo" | "bajarse torneo" | "empezar torneo" => {
+            let torneo = match inter.data.custom_id.as_str() {
+                "anotarse torneo" => {
+                    partidas
+                        .torneos
+                        .inscribir(inter.channel_id, inter.user.id)
+                        .await?
                 }
+                "bajarse torneo" => {
+                    partidas
+                        .torneos
+                        .desinscribir(inter.channel_id, inter.user.id)
+                        .await?
+                }
+                _ => {
+                    partidas
+                        .torneos
+                        .empezar(inter.channel_id, inter.user.id)
+                        .await?
+                }
+            };
+            let (contenido, acciones) = mensaje_torneo(&ctx.http, &torneo).await;
+            inter
+                .create_interaction_response(&ctx.http, |resp| {
+                    resp.kind(InteractionResponseType::UpdateMessage)
+                        .interaction_response_data(|msg| {
+                            msg.content(contenido)
+                                .components(|comps| comps.set_action_rows(acciones))
+                        })
+                })
+                .await
+                .error_generico()?;
+            if torneo.empezado() {
+                empezar_ronda_torneo(&ctx.http, partidas, inter.channel_id).await;
             }
-            if let Some(ganador) = ganador {
-                partidas.terminar_partida(inter.channel_id).await?;
-                fin_partida(
-                    &ctx.http,
-                    estadisticas,
-                    inter.guild_id.unwrap(),
-                    inter.channel_id,
-                    ganador,
-                )
-                .await;
+        }
+        "reintentar torneo" => {
+            let torneo = partidas
+                .torneos
+                .get(inter.channel_id)
+                .await
+                .ok_or_else(|| anyhow!("Este torneo ya no existe :c"))?;
+            if !torneo.mesas_pendientes() {
+                return Err(anyhow!("Todas las mesas ya tienen su hilo uwu"));
             }
+            inter
+                .create_interaction_response(&ctx.http, |resp| {
+                    resp.kind(InteractionResponseType::DeferredUpdateMessage)
+                })
+                .await
+                .error_generico()?;
+            empezar_ronda_torneo(&ctx.http, partidas, inter.channel_id).await;
         }
         _ => return Err(anyhow!("chica q dices")),
     }
@@ -252,26 +391,11 @@ async fn bajar(
         })
         .await
         .error_generico()?;
+    let (mensaje, acciones) = mensaje_tiro(&inter.user.name, pila_levante, carta, turno);
     inter
         .create_followup_message(&ctx.http, |msg| {
-            msg.content(format!(
-                "{}-sama {}tiro un {}\n<@{}> te toca uwu",
-                inter.user.name,
-                match pila_levante {
-                    Some(PilaCartas::Mazo) => "levanto del mazo y ",
-                    Some(PilaCartas::Descartes) => "levanto el descarte y ",
-                    None => "",
-                },
-                carta,
-                turno
-            ))
-            .components(|comps| {
-                comps.create_action_row(|row| {
-                    row.create_button(|btn| {
-                        btn.custom_id(format!("jugar {}", turno)).label("Jugar")
-                    })
-                })
-            })
+            msg.content(mensaje)
+                .components(|comps| comps.set_action_rows(acciones))
         })
         .await
         .error_generico()?;
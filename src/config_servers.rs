@@ -1,6 +1,7 @@
 use anyhow::Result;
 use redis::{aio::MultiplexedConnection, AsyncCommands, FromRedisValue};
 use serenity::model::id::{ChannelId, GuildId};
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct ConfigServers {
@@ -13,7 +14,9 @@ impl ConfigServers {
     }
 
     pub async fn set_canal_partidas(&mut self, guild: GuildId, canal: ChannelId) -> Result<()> {
-        self.redis.set(key_canal_partidas(guild), canal.0).await?;
+        self.redis
+            .set::<_, _, ()>(key_canal_partidas(guild), canal.0)
+            .await?;
         Ok(())
     }
 
@@ -25,8 +28,60 @@ impl ConfigServers {
             Ok(false)
         }
     }
+
+    pub async fn set_recordatorios(
+        &mut self,
+        guild: GuildId,
+        recordatorios: Option<ConfigRecordatorios>,
+    ) -> Result<()> {
+        match recordatorios {
+            Some(recordatorios) => {
+                self.redis
+                    .hset_multiple::<_, _, _, ()>(
+                        key_recordatorios(guild),
+                        &[
+                            ("minutos", recordatorios.minutos),
+                            ("md", recordatorios.md as u64),
+                        ],
+                    )
+                    .await?
+            }
+            None => self.redis.del::<_, ()>(key_recordatorios(guild)).await?,
+        }
+        Ok(())
+    }
+
+    pub async fn get_recordatorios(&self, guild: GuildId) -> Result<Option<ConfigRecordatorios>> {
+        let (minutos, md): (Option<u64>, Option<u64>) = self
+            .redis
+            .clone()
+            .hget(key_recordatorios(guild), &["minutos", "md"])
+            .await?;
+        Ok(minutos
+            .filter(|m| *m > 0)
+            .map(|minutos| ConfigRecordatorios {
+                minutos,
+                md: md == Some(1),
+            }))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ConfigRecordatorios {
+    pub minutos: u64,
+    pub md: bool,
+}
+
+impl ConfigRecordatorios {
+    pub fn intervalo(&self) -> Duration {
+        Duration::from_secs(self.minutos * 60)
+    }
 }
 
 fn key_canal_partidas(guild: GuildId) -> String {
     format!("canal_partidas:{}", guild)
 }
+
+fn key_recordatorios(guild: GuildId) -> String {
+    format!("recordatorios:{}", guild)
+}
@@ -1,8 +1,15 @@
-use crate::errores::ErrorGenerico;
+use crate::{
+    chinchon::es_bot,
+    errores::ErrorGenerico,
+    mensajes::{mencion, nombre_jugador},
+};
 use anyhow::Result;
 use serenity::{
     http::Http,
-    model::id::{ChannelId, MessageId, UserId},
+    model::{
+        application::component::ButtonStyle,
+        id::{ChannelId, MessageId, UserId},
+    },
 };
 
 pub async fn crear_hilo_partida(
@@ -11,15 +18,21 @@ pub async fn crear_hilo_partida(
     mensaje: MessageId,
     jugadores: &[UserId],
     comienza: UserId,
+    parejas: bool,
 ) -> Result<ChannelId> {
     let mut nombres = Vec::with_capacity(jugadores.len());
     for j in jugadores {
-        nombres.push(j.to_user(&http).await.error_generico()?.name);
+        nombres.push(nombre_jugador(http, *j).await);
     }
-    let nombre_canal: String = format!("Chinchon {}", nombres.join(" vs "))
-        .chars()
-        .take(100)
-        .collect();
+    let nombre_canal = if parejas {
+        format!(
+            "Chinchon {} y {} vs {} y {}",
+            nombres[0], nombres[2], nombres[1], nombres[3]
+        )
+    } else {
+        format!("Chinchon {}", nombres.join(" vs "))
+    };
+    let nombre_canal: String = nombre_canal.chars().take(100).collect();
     let canal = canal
         .create_public_thread(&http, mensaje, |t| {
             t.name(nombre_canal).auto_archive_duration(60)
@@ -30,14 +43,38 @@ pub async fn crear_hilo_partida(
     canal
         .send_message(http, |msg| {
             msg.content(format!(
-                "Empieza la partida {menciones} uwu\n\
-                 Es tu turno <@{comienza}>, usa **/jugar** para empezar",
+                "Empieza la partida {menciones} uwu\n{parejas}{turno}",
                 menciones = jugadores
                     .iter()
-                    .map(|id| format!("<@{id}>"))
+                    .map(|id| mencion(*id))
                     .collect::<Vec<_>>()
-                    .join(" ")
+                    .join(" "),
+                parejas = if parejas {
+                    format!(
+                        "Juegan {} y {} contra {} y {}\n",
+                        mencion(jugadores[0]),
+                        mencion(jugadores[2]),
+                        mencion(jugadores[1]),
+                        mencion(jugadores[3])
+                    )
+                } else {
+                    String::new()
+                },
+                turno = if es_bot(comienza) {
+                    format!("Empieza {}", mencion(comienza))
+                } else {
+                    format!("Es tu turno <@{comienza}>, usa **/jugar** para empezar")
+                }
             ))
+            .components(|comps| {
+                comps.create_action_row(|row| {
+                    row.create_button(|btn| {
+                        btn.custom_id("mirar")
+                            .label("Mirar")
+                            .style(ButtonStyle::Secondary)
+                    })
+                })
+            })
         })
         .await
         .error_generico()?;
@@ -1,6 +1,17 @@
-use anyhow::Result;
+use crate::chinchon::{es_bot, ResultadoFinalRonda};
+use anyhow::{anyhow, Result};
 use redis::{aio::MultiplexedConnection, AsyncCommands};
-use serenity::model::id::{GuildId, UserId};
+use rmp_serde::{from_slice, to_vec_named};
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use std::{cmp::Ordering, str::FromStr, time::SystemTime};
+
+pub const RATING_INICIAL: f64 = 1500.0;
+const FACTOR_K: f64 = 32.0;
+pub const MIN_PARTIDAS_RANKING: u64 = 10;
+const MAX_HISTORIAL: isize = 200;
+const SEGUNDOS_NOTACION: usize = 30 * 24 * 60 * 60;
+const CLAVE_RANKINGS_RELLENADOS: &str = "ranking:rellenados";
 
 #[derive(Clone)]
 pub struct Estadisticas {
@@ -11,6 +22,80 @@ pub struct Estadisticas {
 pub struct EstadisticasJugador {
     pub victorias: u64,
     pub derrotas: u64,
+    pub reenganches: u64,
+    pub victorias_parejas: u64,
+    pub derrotas_parejas: u64,
+    pub rating: f64,
+    pub abandonos: u64,
+    pub expulsiones: u64,
+    pub mejor_racha: u64,
+    pub rondas: u64,
+    pub cortes: u64,
+    pub chinchones: u64,
+    pub cortes_cero: u64,
+    pub puntos_sumados: i64,
+    pub max_puntos_sobrevividos: u64,
+}
+
+impl EstadisticasJugador {
+    pub const fn partidas(&self) -> u64 {
+        self.victorias + self.derrotas + self.abandonos + self.expulsiones
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ResumenPartida {
+    pub fecha: SystemTime,
+    pub server: GuildId,
+    pub hilo: ChannelId,
+    pub rondas: usize,
+    pub parejas: bool,
+    pub jugadores: Vec<PosicionFinal>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PosicionFinal {
+    pub jugador: UserId,
+    pub puesto: usize,
+    pub puntos: i16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CriterioRanking {
+    Victorias,
+    Porcentaje,
+    Rating,
+}
+
+impl CriterioRanking {
+    pub const TODOS: [Self; 3] = [Self::Victorias, Self::Porcentaje, Self::Rating];
+
+    pub const fn id(&self) -> &'static str {
+        match self {
+            Self::Victorias => "victorias",
+            Self::Porcentaje => "porcentaje",
+            Self::Rating => "rating",
+        }
+    }
+
+    pub const fn nombre(&self) -> &'static str {
+        match self {
+            Self::Victorias => "Victorias",
+            Self::Porcentaje => "% de victorias",
+            Self::Rating => "Rating",
+        }
+    }
+}
+
+impl FromStr for CriterioRanking {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::TODOS
+            .into_iter()
+            .find(|c| c.id() == s)
+            .ok_or_else(|| anyhow!("No conozco ese ranking :c"))
+    }
 }
 
 impl Estadisticas {
@@ -22,20 +107,351 @@ impl Estadisticas {
         let vict_totales = format!("victorias:total:{}", jugador);
         let vict_server = format!("victorias:{}:{}", server, jugador);
         for clave in [vict_totales, vict_server] {
-            self.redis.incr(clave, 1).await?;
+            self.redis.incr::<_, _, ()>(clave, 1).await?;
         }
-        Ok(())
+        self.redis
+            .zincr::<_, _, _, ()>(format!("ranking:victorias:{server}"), jugador.0, 1)
+            .await?;
+        self.sumar_racha(server, jugador).await?;
+        self.actualizar_porcentaje(server, jugador).await
     }
 
     pub async fn agregar_derrota(&mut self, server: GuildId, jugador: UserId) -> Result<()> {
         let derr_totales = format!("derrotas:total:{}", jugador);
         let derr_server = format!("derrotas:{}:{}", server, jugador);
         for clave in [derr_totales, derr_server] {
-            self.redis.incr(clave, 1).await?;
+            self.redis.incr::<_, _, ()>(clave, 1).await?;
+        }
+        self.cortar_racha(server, jugador).await?;
+        self.actualizar_porcentaje(server, jugador).await
+    }
+
+    pub async fn agregar_abandono(&mut self, server: GuildId, jugador: UserId) -> Result<()> {
+        self.incrementar("abandonos", server, jugador, 1).await?;
+        self.cortar_racha(server, jugador).await?;
+        self.actualizar_porcentaje(server, jugador).await
+    }
+
+    pub async fn agregar_expulsion(&mut self, server: GuildId, jugador: UserId) -> Result<()> {
+        self.incrementar("expulsiones", server, jugador, 1).await?;
+        self.cortar_racha(server, jugador).await?;
+        self.actualizar_porcentaje(server, jugador).await
+    }
+
+    pub async fn agregar_ronda(
+        &mut self,
+        server: GuildId,
+        resultados: &[ResultadoFinalRonda],
+    ) -> Result<()> {
+        for (i, res) in resultados.iter().enumerate() {
+            if es_bot(res.jugador) {
+                continue;
+            }
+            self.incrementar("rondas", server, res.jugador, 1).await?;
+            self.incrementar(
+                "puntos_sumados",
+                server,
+                res.jugador,
+                res.puntos_sumados.into(),
+            )
+            .await?;
+            if !res.perdio {
+                self.maximo("max_puntos", server, res.jugador, res.puntos_total.into())
+                    .await?;
+            }
+            if i == 0 {
+                self.incrementar("cortes", server, res.jugador, 1).await?;
+                if res.chinchon {
+                    self.incrementar("chinchones", server, res.jugador, 1)
+                        .await?;
+                } else if res.sobrantes.is_empty() {
+                    self.incrementar("cortes_cero", server, res.jugador, 1)
+                        .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn incrementar(
+        &mut self,
+        nombre: &str,
+        server: GuildId,
+        jugador: UserId,
+        cant: i64,
+    ) -> Result<()> {
+        for server in [String::from("total"), server.to_string()] {
+            self.redis
+                .incr::<_, _, ()>(format!("{nombre}:{server}:{jugador}"), cant)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn maximo(
+        &mut self,
+        nombre: &str,
+        server: GuildId,
+        jugador: UserId,
+        valor: i64,
+    ) -> Result<()> {
+        for server in [String::from("total"), server.to_string()] {
+            let clave = format!("{nombre}:{server}:{jugador}");
+            let actual: Option<i64> = self.redis.get(&clave).await?;
+            if actual.is_none_or(|actual| valor > actual) {
+                self.redis.set::<_, _, ()>(clave, valor).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn sumar_racha(&mut self, server: GuildId, jugador: UserId) -> Result<()> {
+        for server in [String::from("total"), server.to_string()] {
+            let racha: i64 = self
+                .redis
+                .incr(format!("racha:{server}:{jugador}"), 1)
+                .await?;
+            let clave = format!("mejor_racha:{server}:{jugador}");
+            let mejor: Option<i64> = self.redis.get(&clave).await?;
+            if mejor.is_none_or(|mejor| racha > mejor) {
+                self.redis.set::<_, _, ()>(clave, racha).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// La racha cortada se guarda por si el jugador se reengancha
+    async fn cortar_racha(&mut self, server: GuildId, jugador: UserId) -> Result<()> {
+        for server in [String::from("total"), server.to_string()] {
+            let racha: Option<i64> = self
+                .redis
+                .getset(format!("racha:{server}:{jugador}"), 0)
+                .await?;
+            self.redis
+                .set::<_, _, ()>(
+                    format!("racha_cortada:{server}:{jugador}"),
+                    racha.unwrap_or_default(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn actualizar_porcentaje(&mut self, server: GuildId, jugador: UserId) -> Result<()> {
+        let stats = self.get(jugador, Some(server)).await?;
+        let total = stats.partidas();
+        if total >= MIN_PARTIDAS_RANKING {
+            self.redis
+                .zadd::<_, _, _, ()>(
+                    format!("ranking:porcentaje:{server}"),
+                    jugador.0,
+                    100.0 * stats.victorias as f64 / total as f64,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn agregar_victoria_parejas(
+        &mut self,
+        server: GuildId,
+        jugador: UserId,
+    ) -> Result<()> {
+        for server in [String::from("total"), server.to_string()] {
+            self.redis
+                .incr::<_, _, ()>(format!("victorias_parejas:{server}:{jugador}"), 1)
+                .await?;
+        }
+        self.sumar_racha(server, jugador).await
+    }
+
+    pub async fn agregar_derrota_parejas(
+        &mut self,
+        server: GuildId,
+        jugador: UserId,
+    ) -> Result<()> {
+        for server in [String::from("total"), server.to_string()] {
+            self.redis
+                .incr::<_, _, ()>(format!("derrotas_parejas:{server}:{jugador}"), 1)
+                .await?;
+        }
+        self.cortar_racha(server, jugador).await
+    }
+
+    pub async fn agregar_reenganche(&mut self, server: GuildId, jugador: UserId) -> Result<()> {
+        for server in [String::from("total"), server.to_string()] {
+            self.redis
+                .incr::<_, _, ()>(format!("reenganches:{server}:{jugador}"), 1)
+                .await?;
+            self.redis
+                .decr::<_, _, ()>(format!("derrotas:{server}:{jugador}"), 1)
+                .await?;
+            let cortada: Option<i64> = self
+                .redis
+                .get(format!("racha_cortada:{server}:{jugador}"))
+                .await?;
+            if let Some(racha) = cortada {
+                self.redis
+                    .set::<_, _, ()>(format!("racha:{server}:{jugador}"), racha)
+                    .await?;
+            }
+        }
+        self.actualizar_porcentaje(server, jugador).await
+    }
+
+    pub async fn actualizar_ratings(
+        &mut self,
+        server: GuildId,
+        posiciones: &[Vec<UserId>],
+    ) -> Result<()> {
+        for server in [String::from("total"), server.to_string()] {
+            let mut ratings = Vec::new();
+            for (puesto, grupo) in posiciones.iter().enumerate() {
+                for jugador in grupo {
+                    let rating = self
+                        .redis
+                        .get::<_, Option<f64>>(format!("rating:{server}:{jugador}"))
+                        .await?
+                        .unwrap_or(RATING_INICIAL);
+                    ratings.push((*jugador, puesto, rating));
+                }
+            }
+            for (jugador, nuevo) in calcular_ratings(&ratings) {
+                self.redis
+                    .set::<_, _, ()>(format!("rating:{server}:{jugador}"), nuevo)
+                    .await?;
+                if server != "total" {
+                    self.redis
+                        .zadd::<_, _, _, ()>(format!("ranking:rating:{server}"), jugador.0, nuevo)
+                        .await?;
+                }
+            }
         }
         Ok(())
     }
 
+    /// Los rankings se llenan al terminar cada partida, asi que quienes
+    /// jugaron antes de que existieran se cargan una sola vez desde sus
+    /// estadisticas
+    pub async fn rellenar_rankings(&mut self) -> Result<()> {
+        if self.redis.exists(CLAVE_RANKINGS_RELLENADOS).await? {
+            return Ok(());
+        }
+        for prefijo in ["victorias", "derrotas", "rating"] {
+            let mut claves: Vec<String> = vec![];
+            let mut iter = self
+                .redis
+                .scan_match::<_, String>(format!("{prefijo}:*"))
+                .await?;
+            while let Some(clave) = iter.next_item().await {
+                claves.push(clave);
+            }
+            drop(iter);
+            for clave in claves {
+                let (server, jugador) = match server_y_jugador(&clave) {
+                    Some(ids) => ids,
+                    None => continue,
+                };
+                match prefijo {
+                    "victorias" => {
+                        let victorias: u64 = self.redis.get(&clave).await?;
+                        self.redis
+                            .zadd::<_, _, _, ()>(
+                                format!("ranking:victorias:{server}"),
+                                jugador.0,
+                                victorias,
+                            )
+                            .await?;
+                    }
+                    "rating" => {
+                        let rating: f64 = self.redis.get(&clave).await?;
+                        self.redis
+                            .zadd::<_, _, _, ()>(
+                                format!("ranking:rating:{server}"),
+                                jugador.0,
+                                rating,
+                            )
+                            .await?;
+                        continue;
+                    }
+                    _ => {}
+                }
+                self.actualizar_porcentaje(server, jugador).await?;
+            }
+        }
+        self.redis
+            .set::<_, _, ()>(CLAVE_RANKINGS_RELLENADOS, 1)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn ranking(
+        &self,
+        server: GuildId,
+        criterio: CriterioRanking,
+        desde: usize,
+        cantidad: usize,
+    ) -> Result<(Vec<(UserId, f64)>, usize)> {
+        let mut redis = self.redis.clone();
+        let clave = format!("ranking:{}:{server}", criterio.id());
+        let total: usize = redis.zcard(&clave).await?;
+        let lista: Vec<(u64, f64)> = redis
+            .zrevrange_withscores(&clave, desde as isize, (desde + cantidad) as isize - 1)
+            .await?;
+        Ok((
+            lista.into_iter().map(|(id, v)| (UserId(id), v)).collect(),
+            total,
+        ))
+    }
+
+    pub async fn guardar_partida(&mut self, resumen: &ResumenPartida) -> Result<()> {
+        let id: u64 = self.redis.incr("historial:siguiente", 1).await?;
+        self.redis
+            .set::<_, _, ()>(format!("historial:partida:{id}"), to_vec_named(resumen)?)
+            .await?;
+        for posicion in resumen.jugadores.iter().filter(|p| !es_bot(p.jugador)) {
+            let clave = format!("historial:{}", posicion.jugador);
+            self.redis.lpush::<_, _, ()>(&clave, id).await?;
+            self.redis
+                .ltrim::<_, ()>(&clave, 0, MAX_HISTORIAL - 1)
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn guardar_notacion(&mut self, hilo: ChannelId, notacion: String) -> Result<()> {
+        self.redis
+            .set_ex::<_, _, ()>(format!("notacion:{hilo}"), notacion, SEGUNDOS_NOTACION)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_notacion(&self, hilo: ChannelId) -> Result<Option<String>> {
+        Ok(self.redis.clone().get(format!("notacion:{hilo}")).await?)
+    }
+
+    pub async fn historial(
+        &self,
+        jugador: UserId,
+        desde: usize,
+        cantidad: usize,
+    ) -> Result<(Vec<ResumenPartida>, usize)> {
+        let mut redis = self.redis.clone();
+        let clave = format!("historial:{jugador}");
+        let total: usize = redis.llen(&clave).await?;
+        let ids: Vec<u64> = redis
+            .lrange(&clave, desde as isize, (desde + cantidad) as isize - 1)
+            .await?;
+        let mut partidas = Vec::with_capacity(ids.len());
+        for id in ids {
+            let datos: Option<Vec<u8>> = redis.get(format!("historial:partida:{id}")).await?;
+            if let Some(datos) = datos {
+                partidas.push(from_slice(&datos)?);
+            }
+        }
+        Ok((partidas, total))
+    }
+
     pub async fn get(
         &self,
         jugador: UserId,
@@ -54,6 +470,145 @@ impl Estadisticas {
                 .get::<_, Option<u64>>(format!("derrotas:{server}:{jugador}"))
                 .await?
                 .unwrap_or_default(),
+            reenganches: redis
+                .get::<_, Option<u64>>(format!("reenganches:{server}:{jugador}"))
+                .await?
+                .unwrap_or_default(),
+            victorias_parejas: redis
+                .get::<_, Option<u64>>(format!("victorias_parejas:{server}:{jugador}"))
+                .await?
+                .unwrap_or_default(),
+            derrotas_parejas: redis
+                .get::<_, Option<u64>>(format!("derrotas_parejas:{server}:{jugador}"))
+                .await?
+                .unwrap_or_default(),
+            rating: redis
+                .get::<_, Option<f64>>(format!("rating:{server}:{jugador}"))
+                .await?
+                .unwrap_or(RATING_INICIAL),
+            abandonos: redis
+                .get::<_, Option<u64>>(format!("abandonos:{server}:{jugador}"))
+                .await?
+                .unwrap_or_default(),
+            expulsiones: redis
+                .get::<_, Option<u64>>(format!("expulsiones:{server}:{jugador}"))
+                .await?
+                .unwrap_or_default(),
+            mejor_racha: redis
+                .get::<_, Option<u64>>(format!("mejor_racha:{server}:{jugador}"))
+                .await?
+                .unwrap_or_default(),
+            rondas: redis
+                .get::<_, Option<u64>>(format!("rondas:{server}:{jugador}"))
+                .await?
+                .unwrap_or_default(),
+            cortes: redis
+                .get::<_, Option<u64>>(format!("cortes:{server}:{jugador}"))
+                .await?
+                .unwrap_or_default(),
+            chinchones: redis
+                .get::<_, Option<u64>>(format!("chinchones:{server}:{jugador}"))
+                .await?
+                .unwrap_or_default(),
+            cortes_cero: redis
+                .get::<_, Option<u64>>(format!("cortes_cero:{server}:{jugador}"))
+                .await?
+                .unwrap_or_default(),
+            puntos_sumados: redis
+                .get::<_, Option<i64>>(format!("puntos_sumados:{server}:{jugador}"))
+                .await?
+                .unwrap_or_default(),
+            max_puntos_sobrevividos: redis
+                .get::<_, Option<u64>>(format!("max_puntos:{server}:{jugador}"))
+                .await?
+                .unwrap_or_default(),
         })
     }
 }
+
+/// Lee el server y el jugador de claves como `victorias:{server}:{jugador}`,
+/// salteando las de `total`
+fn server_y_jugador(clave: &str) -> Option<(GuildId, UserId)> {
+    let mut partes = clave.split(':').skip(1);
+    let server = partes.next()?.parse().ok()?;
+    let jugador = partes.next()?.parse().ok()?;
+    partes
+        .next()
+        .is_none()
+        .then_some((GuildId(server), UserId(jugador)))
+}
+
+fn calcular_ratings(ratings: &[(UserId, usize, f64)]) -> Vec<(UserId, f64)> {
+    let rivales = ratings.len().saturating_sub(1).max(1) as f64;
+    ratings
+        .iter()
+        .map(|&(jugador, puesto, rating)| {
+            let cambio: f64 = ratings
+                .iter()
+                .filter(|(otro, ..)| *otro != jugador)
+                .map(|&(_, puesto_otro, rating_otro)| {
+                    let esperado = 1.0 / (1.0 + 10f64.powf((rating_otro - rating) / 400.0));
+                    let real = match puesto.cmp(&puesto_otro) {
+                        Ordering::Less => 1.0,
+                        Ordering::Equal => 0.5,
+                        Ordering::Greater => 0.0,
+                    };
+                    real - esperado
+                })
+                .sum();
+            (jugador, rating + FACTOR_K * cambio / rivales)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cambios(ratings: &[(UserId, usize, f64)]) -> Vec<f64> {
+        calcular_ratings(ratings)
+            .into_iter()
+            .zip(ratings)
+            .map(|((_, nuevo), (_, _, viejo))| nuevo - viejo)
+            .collect()
+    }
+
+    #[test]
+    fn claves_de_estadisticas() {
+        assert_eq!(
+            server_y_jugador("victorias:12:34"),
+            Some((GuildId(12), UserId(34)))
+        );
+        assert_eq!(server_y_jugador("victorias:total:34"), None);
+        assert_eq!(server_y_jugador("rating:12:34:56"), None);
+    }
+
+    #[test]
+    fn ratings_iguales_suman_cero() {
+        let ratings: Vec<_> = (1..=4)
+            .map(|i| (UserId(i), i as usize, RATING_INICIAL))
+            .collect();
+        let cambios = cambios(&ratings);
+        assert!(cambios.iter().sum::<f64>().abs() < 1e-9);
+        assert!(cambios.windows(2).all(|c| c[0] > c[1]));
+    }
+
+    #[test]
+    fn empates_no_cambian_ratings_iguales() {
+        let ratings: Vec<_> = (1..=3).map(|i| (UserId(i), 1, RATING_INICIAL)).collect();
+        assert!(cambios(&ratings).iter().all(|c| c.abs() < 1e-9));
+    }
+
+    #[test]
+    fn empatados_reciben_lo_mismo() {
+        let ratings = [
+            (UserId(1), 1, RATING_INICIAL),
+            (UserId(2), 2, RATING_INICIAL),
+            (UserId(3), 2, RATING_INICIAL),
+        ];
+        let cambios = cambios(&ratings);
+        assert!(cambios[0] > 0.0);
+        assert!((cambios[1] - cambios[2]).abs() < 1e-9);
+        assert!(cambios.iter().sum::<f64>().abs() < 1e-9);
+    }
+}
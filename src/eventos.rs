@@ -1,26 +1,424 @@
-use crate::{estadisticas::Estadisticas, mensajes::mensaje_fin_partida};
+use crate::{
+    chinchon::{es_bot, jugar_turno_bot, nombre_bot, Partida, ResultadoFinalRonda},
+    crear_hilo::crear_hilo_partida,
+    estadisticas::{Estadisticas, PosicionFinal, ResumenPartida},
+    lista_partidas::{ErrorEmpezarPartida, ListaPartidas, RespuestaEmpezarPartida},
+    lista_torneos::ResultadoMesa,
+    mensajes::{
+        acciones_turno, mencion, mensaje_cortar, mensaje_fin_partida, mensaje_tiro, mensaje_torneo,
+        nombre_jugador,
+    },
+};
 use serenity::{
-    builder::CreateActionRow,
     http::Http,
-    model::id::{ChannelId, GuildId, UserId},
+    model::id::{ChannelId, GuildId, MessageId, UserId},
+};
+use std::{
+    iter,
+    sync::Arc,
+    time::{Duration, SystemTime},
 };
+use tokio::{spawn, time::sleep};
+
+pub async fn empezar_partida(
+    http: &Arc<Http>,
+    partidas: &ListaPartidas,
+    canal: ChannelId,
+    creador: UserId,
+) -> Result<RespuestaEmpezarPartida, ErrorEmpezarPartida> {
+    let http = http.clone();
+    partidas
+        .empezar_partida(
+            canal,
+            creador,
+            |mensaje: MessageId, jugadores: Vec<UserId>, comienza: UserId, parejas: bool| async move {
+                crear_hilo_partida(&http, canal, mensaje, &jugadores, comienza, parejas).await
+            },
+        )
+        .await
+}
+
+pub async fn empezar_ronda_torneo(http: &Http, partidas: &ListaPartidas, canal: ChannelId) {
+    let (torneo, mesas) = match partidas.torneos.reservar_mesas(canal).await {
+        Some(reserva) => reserva,
+        None => return,
+    };
+    let num_ronda = torneo.rondas.len();
+    let ronda = torneo.rondas.last().unwrap();
+    let mut fallidas = vec![];
+    for i in mesas {
+        let mesa = &ronda[i];
+        let partida = Partida::empezar(&mesa.jugadores, torneo.reglas);
+        let mensaje = canal
+            .say(
+                http,
+                format!(
+                    "**{} - Ronda {}, mesa {}:** {}",
+                    torneo.nombre,
+                    num_ronda,
+                    i + 1,
+                    mesa.jugadores
+                        .iter()
+                        .map(|j| mencion(*j))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                ),
+            )
+            .await;
+        let hilo = match mensaje {
+            Ok(mensaje) => {
+                crear_hilo_partida(
+                    http,
+                    canal,
+                    mensaje.id,
+                    &mesa.jugadores,
+                    partida.get_turno(),
+                    false,
+                )
+                .await
+            }
+            Err(err) => Err(err.into()),
+        };
+        match hilo {
+            Ok(hilo) => {
+                partidas.agregar_partida(hilo, partida).await;
+                partidas.torneos.asignar_hilo(canal, i, Some(hilo)).await;
+            }
+            Err(_) => {
+                partidas.torneos.asignar_hilo(canal, i, None).await;
+                fallidas.push((i + 1).to_string());
+            }
+        }
+    }
+    if !fallidas.is_empty() {
+        let _ = canal
+            .say(
+                http,
+                format!(
+                    "No pude crear el hilo de la mesa {} :c Toca **Reintentar mesas** en el mensaje del torneo",
+                    fallidas.join(", ")
+                ),
+            )
+            .await;
+    }
+    actualizar_torneo(http, partidas, canal).await;
+}
+
+async fn actualizar_torneo(http: &Http, partidas: &ListaPartidas, canal: ChannelId) {
+    if let Some(torneo) = partidas.torneos.get(canal).await {
+        let (contenido, acciones) = mensaje_torneo(http, &torneo).await;
+        let _ = canal
+            .edit_message(http, torneo.mensaje, |msg| {
+                msg.content(contenido)
+                    .components(|comps| comps.set_action_rows(acciones))
+            })
+            .await;
+    }
+}
 
 pub async fn fin_partida(
     http: &Http,
     estadisticas: &mut Estadisticas,
+    partidas: &ListaPartidas,
     server: GuildId,
     canal: ChannelId,
+    partida: &Partida,
+) {
+    let ganador = match partida.ganador() {
+        Some(ganador) => ganador,
+        None => return,
+    };
+    let pareja = partida.get_pareja(ganador);
+    for jugador in iter::once(ganador).chain(pareja).filter(|j| !es_bot(*j)) {
+        let resultado = if pareja.is_some() {
+            estadisticas.agregar_victoria_parejas(server, jugador).await
+        } else {
+            estadisticas.agregar_victoria(server, jugador).await
+        };
+        if let Err(err) = resultado {
+            eprintln!("Error guardando victoria de {}: {:?}", jugador, err);
+        }
+    }
+    let posiciones: Vec<Vec<_>> = partida
+        .posiciones()
+        .into_iter()
+        .map(|grupo| {
+            grupo
+                .into_iter()
+                .filter(|j| !es_bot(*j))
+                .collect::<Vec<_>>()
+        })
+        .filter(|grupo| !grupo.is_empty())
+        .collect();
+    if posiciones.iter().flatten().count() >= 2 {
+        if let Err(err) = estadisticas.actualizar_ratings(server, &posiciones).await {
+            eprintln!("Error actualizando ratings: {:?}", err);
+        }
+    }
+    guardar_resumen(estadisticas, server, canal, partida).await;
+    let mut mensaje = mensaje_fin_partida(http, server, ganador, pareja).await;
+    mensaje.footer(|f| f.text(format!("Semilla de la partida: {}", partida.get_semilla())));
+    if let Err(err) = canal.send_message(http, |msg| msg.set_embed(mensaje)).await {
+        eprintln!(
+            "Error anunciando el fin de la partida en {}: {:?}",
+            canal, err
+        );
+    }
+    avanzar_torneo(http, partidas, canal, ganador).await;
+}
+
+pub async fn avanzar_torneo(
+    http: &Http,
+    partidas: &ListaPartidas,
+    canal: ChannelId,
     ganador: UserId,
 ) {
-    estadisticas
-        .agregar_victoria(server, ganador)
-        .await
-        .unwrap();
-    let mensaje = mensaje_fin_partida(http, server, ganador).await;
-    canal
-        .send_message(http, |msg| msg.set_embed(mensaje))
+    match partidas.torneos.registrar_ganador(canal, ganador).await {
+        Some((canal_torneo, ResultadoMesa::Campeon(campeon))) => {
+            actualizar_torneo(http, partidas, canal_torneo).await;
+            if let Some(torneo) = partidas.torneos.terminar(canal_torneo).await {
+                let _ = canal_torneo
+                    .say(
+                        http,
+                        format!(
+                            "👑 {} gano el torneo **{}**, felicidades campeon uwu",
+                            mencion(campeon),
+                            torneo.nombre
+                        ),
+                    )
+                    .await;
+            }
+        }
+        Some((canal_torneo, ResultadoMesa::NuevaRonda)) => {
+            empezar_ronda_torneo(http, partidas, canal_torneo).await
+        }
+        Some((canal_torneo, ResultadoMesa::Pendiente)) => {
+            actualizar_torneo(http, partidas, canal_torneo).await
+        }
+        None => {}
+    }
+}
+
+pub async fn guardar_resumen(
+    estadisticas: &mut Estadisticas,
+    server: GuildId,
+    canal: ChannelId,
+    partida: &Partida,
+) {
+    let puntos = partida.get_puntos();
+    let resumen = ResumenPartida {
+        fecha: SystemTime::now(),
+        server,
+        hilo: canal,
+        rondas: partida.get_rondas().len(),
+        parejas: partida.get_reglas().parejas,
+        jugadores: partida
+            .posiciones()
+            .into_iter()
+            .enumerate()
+            .flat_map(|(puesto, grupo)| grupo.into_iter().map(move |jugador| (puesto, jugador)))
+            .map(|(puesto, jugador)| PosicionFinal {
+                jugador,
+                puesto: puesto + 1,
+                puntos: puntos[&jugador],
+            })
+            .collect(),
+    };
+    if let Err(err) = estadisticas.guardar_partida(&resumen).await {
+        eprintln!("Error guardando el resumen de {}: {:?}", canal, err);
+    }
+    if let Err(err) = estadisticas
+        .guardar_notacion(canal, partida.notacion())
         .await
-        .unwrap();
+    {
+        eprintln!("Error guardando la notacion de {}: {:?}", canal, err);
+    }
+}
+
+pub async fn fin_ronda(
+    http: &Http,
+    estadisticas: &mut Estadisticas,
+    partidas: &ListaPartidas,
+    server: GuildId,
+    canal: ChannelId,
+    resultados: &[ResultadoFinalRonda],
+    ganador: Option<UserId>,
+) {
+    if let Err(err) = estadisticas.agregar_ronda(server, resultados).await {
+        eprintln!("Error guardando la ronda de {}: {:?}", canal, err);
+    }
+    for res in resultados {
+        if res.perdio {
+            perdio(estadisticas, server, res.jugador, res.pareja.is_some()).await;
+        }
+    }
+    if ganador.is_some() {
+        if let Ok(partida) = partidas.terminar_partida(canal).await {
+            fin_partida(http, estadisticas, partidas, server, canal, &partida).await;
+        }
+    }
+}
+
+pub fn jugar_turnos_bots(
+    http: &Arc<Http>,
+    estadisticas: &Estadisticas,
+    partidas: &Arc<ListaPartidas>,
+    server: GuildId,
+    canal: ChannelId,
+) {
+    let mut activos = match TurnosBotsActivos::empezar(partidas, canal) {
+        Some(activos) => activos,
+        None => return,
+    };
+    let http = http.clone();
+    let mut estadisticas = estadisticas.clone();
+    let partidas = partidas.clone();
+    spawn(async move {
+        turnos_bots(
+            &http,
+            &mut estadisticas,
+            &partidas,
+            &mut activos,
+            server,
+            canal,
+        )
+        .await;
+    });
+}
+
+/// Al reiniciar el bot nadie vuelve a mover a los bots de las partidas
+/// guardadas, asi que se arrancan aca las que quedaron en turno de un bot.
+pub async fn reanudar_turnos_bots(
+    http: &Arc<Http>,
+    estadisticas: &Estadisticas,
+    partidas: &Arc<ListaPartidas>,
+) {
+    for (canal, partida) in partidas.activas().await {
+        {
+            let partida = partida.lock().await;
+            if partida.ganador().is_some() || partida.get_bot(partida.get_turno()).is_none() {
+                continue;
+            }
+        }
+        match canal.to_channel(http).await.ok().and_then(|c| c.guild()) {
+            Some(canal_guild) => {
+                jugar_turnos_bots(http, estadisticas, partidas, canal_guild.guild_id, canal)
+            }
+            None => eprintln!("No se pudo reanudar a los bots de {}", canal),
+        }
+    }
+}
+
+/// Marca que la partida ya tiene una tarea jugando los turnos de los bots.
+/// La marca se quita al soltarla aunque la tarea termine por un panic.
+struct TurnosBotsActivos {
+    partidas: Arc<ListaPartidas>,
+    canal: ChannelId,
+    activos: bool,
+}
+
+impl TurnosBotsActivos {
+    fn empezar(partidas: &Arc<ListaPartidas>, canal: ChannelId) -> Option<Self> {
+        partidas.empezar_turnos_bots(canal).then(|| Self {
+            partidas: partidas.clone(),
+            canal,
+            activos: true,
+        })
+    }
+
+    fn terminar(&mut self) {
+        if self.activos {
+            self.partidas.terminar_turnos_bots(self.canal);
+            self.activos = false;
+        }
+    }
+}
+
+impl Drop for TurnosBotsActivos {
+    fn drop(&mut self) {
+        self.terminar();
+    }
+}
+
+async fn turnos_bots(
+    http: &Http,
+    estadisticas: &mut Estadisticas,
+    partidas: &ListaPartidas,
+    activos: &mut TurnosBotsActivos,
+    server: GuildId,
+    canal: ChannelId,
+) {
+    loop {
+        let partida = match partidas.get_partida(canal).await {
+            Some(partida) => partida,
+            None => return,
+        };
+        let mut partida = partida.lock().await;
+        let turno = partida.get_turno();
+        let dificultad = match partida.get_bot(turno) {
+            Some(dificultad) if partida.ganador().is_none() => dificultad,
+            _ => {
+                activos.terminar();
+                return;
+            }
+        };
+        let quedan_humanos = partida
+            .get_jugadores()
+            .into_iter()
+            .any(|j| !es_bot(j) && partida.esta_en_juego(j));
+        if !quedan_humanos {
+            activos.terminar();
+            drop(partida);
+            if let Ok(partida) = partidas.terminar_partida(canal).await {
+                guardar_resumen(estadisticas, server, canal, &partida).await;
+            }
+            let _ = canal
+                .say(
+                    http,
+                    "Ya no quedan humanos en la mesa asi que la partida termina aca uwu",
+                )
+                .await;
+            return;
+        }
+        let jugada = jugar_turno_bot(&mut partida.jugador(turno).unwrap(), dificultad);
+        let prox_turno = partida.get_turno();
+        let ganador = partida.ganador();
+        drop(partida);
+        let (mensaje, acciones) = match &jugada.corte {
+            Some(resultados) => {
+                mensaje_cortar(
+                    http,
+                    resultados,
+                    &nombre_bot(turno),
+                    ganador.is_none().then_some(prox_turno),
+                )
+                .await
+            }
+            None => mensaje_tiro(&nombre_bot(turno), jugada.levanto, jugada.carta, prox_turno),
+        };
+        if let Err(err) = canal
+            .send_message(http, |msg| {
+                msg.content(mensaje)
+                    .components(|comps| comps.set_action_rows(acciones))
+            })
+            .await
+        {
+            eprintln!("Error mandando la jugada del bot en {}: {:?}", canal, err);
+        }
+        if let Some(resultados) = &jugada.corte {
+            fin_ronda(
+                http,
+                estadisticas,
+                partidas,
+                server,
+                canal,
+                resultados,
+                ganador,
+            )
+            .await;
+        }
+        sleep(Duration::from_secs(1)).await;
+    }
 }
 
 pub async fn abandono(
@@ -29,16 +427,63 @@ pub async fn abandono(
     server: GuildId,
     canal: ChannelId,
     jugador: UserId,
+    pareja: Option<UserId>,
     cambio_turno: Option<UserId>,
 ) {
-    let nombre_jugador = jugador.to_user(http).await.unwrap().name;
-    let mut cont = format!("**{}** abandono la partida u.u", nombre_jugador);
+    let nombre = nombre_jugador(http, jugador).await;
+    let cont = format!("**{}** abandono la partida u.u", nombre);
+    anunciar_salida(http, canal, cont, pareja, cambio_turno).await;
+    if !es_bot(jugador) {
+        estadisticas
+            .agregar_abandono(server, jugador)
+            .await
+            .unwrap();
+    }
+    if let Some(pareja) = pareja {
+        perdio(estadisticas, server, pareja, true).await;
+    }
+}
+
+pub async fn expulsion(
+    http: &Http,
+    estadisticas: &mut Estadisticas,
+    server: GuildId,
+    canal: ChannelId,
+    jugador: UserId,
+    pareja: Option<UserId>,
+    cambio_turno: Option<UserId>,
+) {
+    let nombre = nombre_jugador(http, jugador).await;
+    let cont = format!("**{}** fue expulsado de la partida >:c", nombre);
+    anunciar_salida(http, canal, cont, pareja, cambio_turno).await;
+    if !es_bot(jugador) {
+        estadisticas
+            .agregar_expulsion(server, jugador)
+            .await
+            .unwrap();
+    }
+    if let Some(pareja) = pareja {
+        perdio(estadisticas, server, pareja, true).await;
+    }
+}
+
+async fn anunciar_salida(
+    http: &Http,
+    canal: ChannelId,
+    mut cont: String,
+    pareja: Option<UserId>,
+    cambio_turno: Option<UserId>,
+) {
+    if let Some(pareja) = pareja {
+        cont += &format!(
+            "\nSu pareja **{}** tambien queda fuera :c",
+            nombre_jugador(http, pareja).await
+        );
+    }
     let mut accs = vec![];
     if let Some(turno) = cambio_turno {
-        cont += &format!("\nAhora es el turno de <@{}>", turno);
-        let mut row = CreateActionRow::default();
-        row.create_button(|btn| btn.custom_id(format!("jugar {}", turno)).label("Jugar"));
-        accs.push(row);
+        cont += &format!("\nAhora es el turno de {}", mencion(turno));
+        accs = acciones_turno(turno);
     }
     canal
         .send_message(http, |msg| {
@@ -47,9 +492,30 @@ pub async fn abandono(
         })
         .await
         .unwrap();
-    perdio(estadisticas, server, jugador).await;
 }
 
-pub async fn perdio(estadisticas: &mut Estadisticas, server: GuildId, jugador: UserId) {
-    estadisticas.agregar_derrota(server, jugador).await.unwrap();
+pub async fn perdio(
+    estadisticas: &mut Estadisticas,
+    server: GuildId,
+    jugador: UserId,
+    parejas: bool,
+) {
+    if es_bot(jugador) {
+        return;
+    }
+    let resultado = if parejas {
+        estadisticas.agregar_derrota_parejas(server, jugador).await
+    } else {
+        estadisticas.agregar_derrota(server, jugador).await
+    };
+    if let Err(err) = resultado {
+        eprintln!("Error guardando derrota de {}: {:?}", jugador, err);
+    }
+}
+
+pub async fn reenganche(estadisticas: &mut Estadisticas, server: GuildId, jugador: UserId) {
+    estadisticas
+        .agregar_reenganche(server, jugador)
+        .await
+        .unwrap();
 }
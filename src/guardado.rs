@@ -0,0 +1,174 @@
+use crate::lista_partidas::ListaPartidas;
+use anyhow::Result;
+use rmp_serde::{encode::write_named, from_slice};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::BTreeSet,
+    fs::{read, rename, File},
+    io::{self, BufWriter, Write},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::time::interval;
+
+const INTERVALO_GUARDADO: Duration = Duration::from_secs(10);
+const FORMATO: &str = "chinchoncito";
+pub const VERSION_GUARDADO: u32 = 1;
+
+/// Archivos de una version mas nueva del bot, que no se pisan para poder
+/// volver a esa version sin perder nada
+static PROTEGIDOS: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+#[derive(Serialize)]
+struct Sobre<'a, T> {
+    formato: &'a str,
+    version: u32,
+    datos: &'a T,
+}
+
+#[derive(Deserialize)]
+struct Encabezado {
+    formato: String,
+    version: u32,
+}
+
+#[derive(Deserialize)]
+struct SobreLeido<T> {
+    datos: T,
+}
+
+pub fn escribir_atomico<T: Serialize>(ruta: &str, datos: &T) -> Result<()> {
+    if PROTEGIDOS.lock().unwrap().contains(ruta) {
+        return Ok(());
+    }
+    let temporal = format!("{ruta}.tmp");
+    let arch = File::create(&temporal)?;
+    let mut escritor = BufWriter::new(arch);
+    let sobre = Sobre {
+        formato: FORMATO,
+        version: VERSION_GUARDADO,
+        datos,
+    };
+    write_named(&mut escritor, &sobre)?;
+    escritor.flush()?;
+    escritor.get_ref().sync_all()?;
+    rename(&temporal, ruta)?;
+    Ok(())
+}
+
+pub fn leer_guardado<T: DeserializeOwned + Default>(
+    ruta: &str,
+    migrar: fn(u32, &[u8]) -> Result<T>,
+) -> Result<T> {
+    let bytes = match read(ruta) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(e) => return Err(e.into()),
+    };
+    let leido = match from_slice::<Encabezado>(&bytes) {
+        Ok(Encabezado { formato, version }) if formato == FORMATO => {
+            if version == VERSION_GUARDADO {
+                from_slice::<SobreLeido<T>>(&bytes)
+                    .map(|s| s.datos)
+                    .map_err(|e| e.into())
+            } else if version > VERSION_GUARDADO {
+                eprintln!(
+                    "ERROR: {ruta} tiene la version {version} y este bot solo entiende hasta la {VERSION_GUARDADO}. \
+                    Se empieza sin esos datos y el archivo no se va a tocar hasta actualizar el bot"
+                );
+                PROTEGIDOS.lock().unwrap().insert(ruta.to_owned());
+                return Ok(T::default());
+            } else {
+                migrar(version, &bytes)
+            }
+        }
+        _ => migrar(0, &bytes),
+    };
+    leido.or_else(|err| {
+        let segundos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let cuarentena = format!("{ruta}.corrupto-{segundos}");
+        eprintln!("No se pudo leer {ruta}, se movio a {cuarentena}: {err:?}");
+        rename(ruta, &cuarentena)?;
+        Ok(T::default())
+    })
+}
+
+pub async fn guardado_periodico(partidas: Arc<ListaPartidas>) {
+    let mut intervalo = interval(INTERVALO_GUARDADO);
+    loop {
+        intervalo.tick().await;
+        if let Err(err) = partidas.guardar().await {
+            eprintln!("Error guardando partidas: {:?}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use std::{env::temp_dir, fs::read_dir, process};
+
+    fn ruta_temporal(nombre: &str) -> String {
+        let dir = temp_dir().join(format!("chinchoncito-{}-{nombre}", process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("datos").to_str().unwrap().to_owned()
+    }
+
+    fn sin_migracion(_: u32, _: &[u8]) -> Result<Vec<u32>> {
+        Err(anyhow!("no se puede migrar"))
+    }
+
+    #[test]
+    fn guardado_ida_y_vuelta() {
+        let ruta = ruta_temporal("ida-y-vuelta");
+        escribir_atomico(&ruta, &vec![1u32, 2, 3]).unwrap();
+        assert_eq!(leer_guardado(&ruta, sin_migracion).unwrap(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn archivo_corrupto_va_a_cuarentena() {
+        let ruta = ruta_temporal("corrupto");
+        std::fs::write(&ruta, b"esto no es un guardado").unwrap();
+        assert_eq!(
+            leer_guardado(&ruta, sin_migracion).unwrap(),
+            Vec::<u32>::new()
+        );
+        assert!(read(&ruta).is_err());
+        let dir = std::path::Path::new(&ruta).parent().unwrap();
+        let cuarentena: Vec<_> = read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .filter(|n| n.starts_with("datos.corrupto-"))
+            .collect();
+        assert_eq!(cuarentena.len(), 1);
+        let segundos = cuarentena[0].trim_start_matches("datos.corrupto-");
+        assert!(segundos.parse::<u64>().is_ok());
+        assert_eq!(
+            read(dir.join(&cuarentena[0])).unwrap(),
+            b"esto no es un guardado"
+        );
+    }
+
+    #[test]
+    fn version_nueva_no_se_toca() {
+        let ruta = ruta_temporal("version-nueva");
+        let bytes = rmp_serde::to_vec_named(&Sobre {
+            formato: FORMATO,
+            version: VERSION_GUARDADO + 1,
+            datos: &vec![7u32],
+        })
+        .unwrap();
+        std::fs::write(&ruta, &bytes).unwrap();
+        assert_eq!(
+            leer_guardado(&ruta, sin_migracion).unwrap(),
+            Vec::<u32>::new()
+        );
+        escribir_atomico(&ruta, &Vec::<u32>::new()).unwrap();
+        assert_eq!(read(&ruta).unwrap(), bytes);
+    }
+}
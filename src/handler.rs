@@ -1,17 +1,17 @@
 use crate::{
     comandos::procesar_comando, componentes::inter_componente, config_servers::ConfigServers,
-    estadisticas::Estadisticas, lista_partidas::ListaPartidas,
+    estadisticas::Estadisticas, eventos::reanudar_turnos_bots, lista_partidas::ListaPartidas,
 };
 use serenity::{
     async_trait,
     client::{Context, EventHandler},
     model::{
+        application::{
+            command::{Command, CommandOptionType},
+            interaction::Interaction,
+        },
         channel::{ChannelType, Message},
         gateway::Ready,
-        interactions::{
-            application_command::{ApplicationCommand, ApplicationCommandOptionType},
-            Interaction,
-        },
         Permissions,
     },
 };
@@ -104,7 +104,7 @@ impl EventHandler for Handler {
 
     async fn ready(&self, ctx: Context, _: Ready) {
         println!("Conectado!");
-        ApplicationCommand::set_global_application_commands(&ctx.http, |x| {
+        Command::set_global_application_commands(&ctx.http, |x| {
             x.create_application_command(|c| {
                 c.name("chinchon")
                     .description("Empezar una partida")
@@ -112,14 +112,118 @@ impl EventHandler for Handler {
                     .create_option(|opt| {
                         opt.name("privada")
                             .description("Si quieres elegir quien puede unirse con /invitar")
-                            .kind(ApplicationCommandOptionType::String)
+                            .kind(CommandOptionType::String)
                             .add_string_choice("Sí", "true")
                             .add_string_choice("No", "false")
                     })
                     .create_option(|opt| {
                         opt.name("jugadores")
                             .description("El limite de jugadores. Por defecto es 2, maximo 4")
-                            .kind(ApplicationCommandOptionType::Integer)
+                            .kind(CommandOptionType::Integer)
+                    })
+                    .create_option(|opt| {
+                        opt.name("bots")
+                            .description("Cuantos lugares ocupa la compu")
+                            .kind(CommandOptionType::Integer)
+                            .min_int_value(0)
+                            .max_int_value(3)
+                    })
+                    .create_option(|opt| {
+                        opt.name("dificultad")
+                            .description("Que tan bien juegan los bots. Por defecto es facil")
+                            .kind(CommandOptionType::String)
+                            .add_string_choice("Facil", "facil")
+                            .add_string_choice("Dificil", "dificil")
+                    })
+                    .create_option(|opt| {
+                        opt.name("limite")
+                            .description("Los puntos con los que se pierde. Por defecto es 100")
+                            .kind(CommandOptionType::Integer)
+                            .add_int_choice("50", 50)
+                            .add_int_choice("100", 100)
+                            .add_int_choice("150", 150)
+                    })
+                    .create_option(|opt| {
+                        opt.name("corta_con")
+                            .description("Maximo de puntos sueltos para poder cortar. Por defecto es 5")
+                            .kind(CommandOptionType::Integer)
+                            .min_int_value(0)
+                            .max_int_value(10)
+                    })
+                    .create_option(|opt| {
+                        opt.name("comodines")
+                            .description("Jugar con comodines y cuanto suman si quedan sueltos")
+                            .kind(CommandOptionType::Integer)
+                            .add_int_choice("25", 25)
+                            .add_int_choice("50", 50)
+                    })
+                    .create_option(|opt| {
+                        opt.name("cartas")
+                            .description("Jugar con el mazo de 48 cartas o el de 40 sin ochos ni nueves")
+                            .kind(CommandOptionType::Integer)
+                            .add_int_choice("48", 48)
+                            .add_int_choice("40", 40)
+                    })
+                    .create_option(|opt| {
+                        opt.name("reenganche")
+                            .description("Si quien se pasa puede volver una vez con los puntos del que va peor")
+                            .kind(CommandOptionType::String)
+                            .add_string_choice("Sí", "true")
+                            .add_string_choice("No", "false")
+                    })
+                    .create_option(|opt| {
+                        opt.name("acomodar")
+                            .description("Si al cortar los demas pueden acomodar sus cartas en los juegos de quien corta")
+                            .kind(CommandOptionType::String)
+                            .add_string_choice("Sí", "true")
+                            .add_string_choice("No", "false")
+                    })
+                    .create_option(|opt| {
+                        opt.name("parejas")
+                            .description("Jugar 2 contra 2 y como se juntan los puntos de cada pareja")
+                            .kind(CommandOptionType::String)
+                            .add_string_choice("Sumando los puntos", "suma")
+                            .add_string_choice("Los de quien va peor", "maximo")
+                    })
+                    .create_option(|opt| {
+                        opt.name("tiempo")
+                            .description("Cuanto tiene cada uno para jugar su turno antes de que se juegue solo")
+                            .kind(CommandOptionType::Integer)
+                            .add_int_choice("1 minuto", 60)
+                            .add_int_choice("3 minutos", 180)
+                            .add_int_choice("10 minutos", 600)
+                    })
+                    .create_option(|opt| {
+                        opt.name("pareja")
+                            .description("Con quien quieres jugar en pareja. Si no eliges se sortea")
+                            .kind(CommandOptionType::User)
+                    })
+            })
+            .create_application_command(|c| {
+                c.name("torneo")
+                    .description("Organizar un torneo, los ganadores de cada mesa pasan a la siguiente ronda")
+                    .dm_permission(false)
+                    .default_member_permissions(Permissions::MANAGE_CHANNELS)
+                    .create_option(|opt| {
+                        opt.name("nombre")
+                            .description("Como se llama el torneo")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+                    .create_option(|opt| {
+                        opt.name("jugadores")
+                            .description("Cuantos juegan en cada mesa. Por defecto es 2, maximo 4")
+                            .kind(CommandOptionType::Integer)
+                            .min_int_value(2)
+                            .max_int_value(4)
+                    })
+                    .create_option(|opt| {
+                        opt.name("limite")
+                            .description("Los puntos con los que se pierde. Por defecto es 100")
+                            .kind(CommandOptionType::Integer)
+                            .add_int_choice("50", 50)
+                            .add_int_choice("100", 100)
+                            .add_int_choice("150", 150)
                     })
             })
             .create_application_command(|c| {
@@ -128,10 +232,33 @@ impl EventHandler for Handler {
                     .create_option(|opt| {
                         opt.name("jugador")
                             .description("De quien quieres ver las estadisticas. Omitelo para ver las tuyas")
-                            .kind(ApplicationCommandOptionType::User)
+                            .kind(CommandOptionType::User)
                             .required(false)
                     })
             })
+            .create_application_command(|c| {
+                c.name("historial")
+                    .description("Ver las ultimas partidas de alguien")
+                    .create_option(|opt| {
+                        opt.name("jugador")
+                            .description("De quien quieres ver las partidas. Omitelo para ver las tuyas")
+                            .kind(CommandOptionType::User)
+                            .required(false)
+                    })
+            })
+            .create_application_command(|c| {
+                c.name("ranking")
+                    .description("Ver quienes son los mejores jugadores del servidor")
+                    .dm_permission(false)
+                    .create_option(|opt| {
+                        opt.name("por")
+                            .description("Como ordenar el ranking. Por defecto por victorias")
+                            .kind(CommandOptionType::String)
+                            .add_string_choice("Victorias", "victorias")
+                            .add_string_choice("Porcentaje de victorias", "porcentaje")
+                            .add_string_choice("Rating", "rating")
+                    })
+            })
             .create_application_command(|c| {
                 c.name("invitar")
                     .description("Autorizar a alguien a unirse a tu partida. Solo funciona en mesas privadas")
@@ -139,7 +266,7 @@ impl EventHandler for Handler {
                     .create_option(|opt| {
                        opt.name("a")
                            .description("A quien quieres invitar")
-                           .kind(ApplicationCommandOptionType::User)
+                           .kind(CommandOptionType::User)
                            .required(true)
                     })
             })
@@ -158,6 +285,16 @@ impl EventHandler for Handler {
                     .description("Ver los puntajes de la partida en la que estas")
                     .dm_permission(false)
             })
+            .create_application_command(|c| {
+                c.name("ver")
+                    .description("Ver como va la partida de este hilo sin ver las cartas de nadie")
+                    .dm_permission(false)
+            })
+            .create_application_command(|c| {
+                c.name("exportar")
+                    .description("Descargar la partida de este hilo en texto, jugada por jugada")
+                    .dm_permission(false)
+            })
             .create_application_command(|c| {
                 c.name("empezar")
                     .description("Si creaste una partida y todavia no se llena usa este comando para empezarla igual")
@@ -171,11 +308,32 @@ impl EventHandler for Handler {
                     .create_option(|o| {
                         o.name("canal")
                             .description("El canal para crear partidas")
-                            .kind(ApplicationCommandOptionType::Channel)
+                            .kind(CommandOptionType::Channel)
                             .channel_types(&[ChannelType::Text])
                             .required(true)
                     })
             })
+            .create_application_command(|c| {
+                c.name("recordatorios")
+                    .description("Recordar su turno a quien tarda mucho en jugar")
+                    .dm_permission(false)
+                    .default_member_permissions(Permissions::MANAGE_CHANNELS)
+                    .create_option(|o| {
+                        o.name("minutos")
+                            .description("Cada cuantos minutos sin jugar se avisa, 0 para no avisar")
+                            .kind(CommandOptionType::Integer)
+                            .min_int_value(0)
+                            .max_int_value(24 * 60)
+                            .required(true)
+                    })
+                    .create_option(|o| {
+                        o.name("md")
+                            .description("Avisar tambien por mensaje directo")
+                            .kind(CommandOptionType::String)
+                            .add_string_choice("Sí", "true")
+                            .add_string_choice("No", "false")
+                    })
+            })
             .create_application_command(|c| {
                 c.name("kick")
                     .description("Vota para expulsar a alguien de una partida")
@@ -183,7 +341,7 @@ impl EventHandler for Handler {
                     .create_option(|o| {
                         o.name("a")
                             .description("A quien quieres expulsar")
-                            .kind(ApplicationCommandOptionType::User)
+                            .kind(CommandOptionType::User)
                             .required(true)
                     })
             })
@@ -195,5 +353,6 @@ impl EventHandler for Handler {
         })
         .await
         .expect("Error creando comandos");
+        reanudar_turnos_bots(&ctx.http, &self.0.estadisticas, &self.0.partidas).await;
     }
 }
@@ -0,0 +1,24 @@
+use crate::lista_partidas::ListaPartidas;
+use serenity::http::Http;
+use std::{sync::Arc, time::Duration};
+use tokio::time::interval;
+
+const INTERVALO_REVISION: Duration = Duration::from_secs(30);
+
+pub async fn vencer_invitaciones(http: Arc<Http>, partidas: Arc<ListaPartidas>, limite: Duration) {
+    let mut intervalo = interval(INTERVALO_REVISION);
+    loop {
+        intervalo.tick().await;
+        for (canal, mensaje) in partidas.quitar_invitaciones_vencidas(limite).await {
+            let _ = canal
+                .edit_message(&http, mensaje, |msg| {
+                    msg.content(
+                        "Esta invitacion vencio porque nadie mas se unio :c
+Usa **/chinchon** para crear otra",
+                    )
+                    .components(|comps| comps.set_action_rows(vec![]))
+                })
+                .await;
+        }
+    }
+}
@@ -1,14 +1,18 @@
-use crate::chinchon::Partida;
+use crate::{
+    chinchon::{id_bot, Dificultad, Partida, ReglasPartida},
+    guardado::{escribir_atomico, leer_guardado},
+    lista_torneos::ListaTorneos,
+};
 use anyhow::{anyhow, Result};
-use rmp_serde::{encode::write_named, from_read};
+use rand::{prelude::SliceRandom, thread_rng};
+use rmp_serde::from_slice;
+use serde::{Deserialize, Serialize};
 use serenity::model::id::{ChannelId, MessageId, UserId};
 use std::{
     collections::{HashMap, HashSet},
-    fs::File,
     future::Future,
-    io,
     sync::Arc,
-    time::Duration,
+    time::{Duration, SystemTime},
 };
 use tokio::{
     sync::{Mutex, RwLock},
@@ -19,48 +23,71 @@ use tokio::{
 pub struct ListaPartidas {
     invitaciones: RwLock<HashMap<(ChannelId, UserId), (Invitacion, MessageId)>>,
     partidas: RwLock<HashMap<ChannelId, Arc<Mutex<Partida>>>>,
+    pub torneos: ListaTorneos,
+    guardando: Mutex<()>,
+    turnos_bots: std::sync::Mutex<HashSet<ChannelId>>,
 }
 
 impl ListaPartidas {
     pub async fn cargar() -> Result<Self> {
         let partidas = spawn_blocking::<_, Result<_>>(|| {
-            let partidas: Vec<(ChannelId, Partida)> = match File::open("partidas") {
-                Ok(arch) => from_read(arch)?,
-                Err(e) => match e.kind() {
-                    io::ErrorKind::NotFound => vec![],
-                    _ => Err(e)?,
-                },
-            };
+            let partidas: Vec<(ChannelId, Partida)> = leer_guardado("partidas", migrar_partidas)?;
             Ok(partidas
                 .into_iter()
+                .filter(|(canal, partida)| match partida.validar() {
+                    Ok(()) => true,
+                    Err(errores) => {
+                        eprintln!("Descartando la partida de {} porque es invalida:", canal);
+                        for error in errores {
+                            eprintln!("  - {}", error);
+                        }
+                        false
+                    }
+                })
                 .map(|(c, p)| (c, Arc::new(Mutex::new(p))))
                 .collect())
         })
         .await
         .unwrap()?;
+        let invitaciones = spawn_blocking::<_, Result<_>>(|| {
+            let invitaciones = leer_guardado("invitaciones", migrar_invitaciones)?;
+            Ok(invitaciones
+                .into_iter()
+                .map(|(canal, creador, invi, mensaje)| ((canal, creador), (invi, mensaje)))
+                .collect())
+        })
+        .await
+        .unwrap()?;
         Ok(Self {
+            invitaciones: RwLock::new(invitaciones),
             partidas: RwLock::new(partidas),
+            torneos: ListaTorneos::cargar().await?,
             ..Default::default()
         })
     }
 
     pub async fn guardar(&self) -> Result<()> {
+        let _guardando = self.guardando.lock().await;
         let partidas = self.partidas.read().await;
         let mut lista = Vec::with_capacity(partidas.len());
         for (canal, partida) in partidas.iter() {
-            let partida = partida.lock().await.clone();
-            if partida.tiempo_inactiva() < Duration::from_secs(24 * 60 * 60) {
-                lista.push((*canal, partida));
-            }
+            lista.push((*canal, partida.lock().await.clone()));
         }
         drop(partidas);
+        let invitaciones: Vec<_> = self
+            .invitaciones
+            .read()
+            .await
+            .iter()
+            .map(|((canal, creador), (invi, mensaje))| (*canal, *creador, invi.clone(), *mensaje))
+            .collect();
         spawn_blocking(move || {
-            let mut arch = File::create("partidas")?;
-            write_named(&mut arch, &lista)?;
-            Ok(())
+            escribir_atomico("partidas", &lista)?;
+            escribir_atomico("invitaciones", &invitaciones)
         })
         .await
-        .unwrap()
+        .unwrap()?;
+        self.torneos.guardar().await
     }
 
     pub async fn crear_invitacion(
@@ -68,16 +95,34 @@ impl ListaPartidas {
         canal: ChannelId,
         creador: UserId,
         mensaje: MessageId,
-        invitados: Option<Vec<UserId>>,
-        max_jugadores: usize,
+        invitacion: Invitacion,
     ) -> Option<(ChannelId, MessageId)> {
-        let invitacion_vieja = self.invitaciones.write().await.insert(
-            (canal, creador),
-            (Invitacion::new(creador, invitados, max_jugadores), mensaje),
-        );
+        let invitacion_vieja = self
+            .invitaciones
+            .write()
+            .await
+            .insert((canal, creador), (invitacion, mensaje));
         invitacion_vieja.map(|i| (canal, i.1))
     }
 
+    pub async fn quitar_invitaciones_vencidas(
+        &self,
+        limite: Duration,
+    ) -> Vec<(ChannelId, MessageId)> {
+        let mut vencidas = vec![];
+        self.invitaciones
+            .write()
+            .await
+            .retain(|(canal, _), (invi, mensaje)| {
+                let vencida = invi.vencida(limite);
+                if vencida {
+                    vencidas.push((*canal, *mensaje));
+                }
+                !vencida
+            });
+        vencidas
+    }
+
     pub async fn agregar_invitado(
         &self,
         canal: ChannelId,
@@ -88,9 +133,9 @@ impl ListaPartidas {
             .write()
             .await
             .get_mut(&(canal, creador))
-            .ok_or_else(|| anyhow!(
-                "No creaste ninguna invitacion, usa **/chinchon** para crear una"
-            ))?
+            .ok_or_else(|| {
+                anyhow!("No creaste ninguna invitacion, usa **/chinchon** para crear una")
+            })?
             .0
             .agregar_invitado(invitado)
     }
@@ -117,7 +162,7 @@ impl ListaPartidas {
         &self,
         canal_inv: ChannelId,
         creador_inv: UserId,
-        crear_canal: impl FnOnce(MessageId, Vec<UserId>, UserId) -> Fut,
+        crear_canal: impl FnOnce(MessageId, Vec<UserId>, UserId, bool) -> Fut,
     ) -> std::result::Result<RespuestaEmpezarPartida, ErrorEmpezarPartida>
     where
         Fut: Future<Output = Result<ChannelId>>,
@@ -127,62 +172,132 @@ impl ListaPartidas {
             .get(&(canal_inv, creador_inv))
             .ok_or(ErrorEmpezarPartida::InvitacionNoExiste)?
             .clone();
-        if invitacion.jugadores().len() < 2 {
+        let total = invitacion.jugadores().len() + invitacion.bots.len();
+        if total < 2 || (invitacion.reglas.parejas && total != 4) {
             return Err(ErrorEmpezarPartida::PocosJugadores);
         }
         invitaciones.remove(&(canal_inv, creador_inv)).unwrap();
         drop(invitaciones);
-        let jugadores = invitacion.jugadores();
-        let partida = Partida::empezar(&jugadores);
+        let mut jugadores = invitacion.jugadores();
+        let bots: Vec<_> = (0..)
+            .map(id_bot)
+            .zip(invitacion.bots.iter().copied())
+            .collect();
+        jugadores.extend(bots.iter().map(|(id, _)| *id));
+        if invitacion.reglas.parejas {
+            jugadores = sentar_parejas(creador_inv, invitacion.pareja, jugadores);
+        }
+        let mut partida = Partida::empezar(&jugadores, invitacion.reglas);
+        for (id, dificultad) in bots {
+            partida.set_bot(id, dificultad);
+        }
         let comienza = partida.get_turno();
-        let canal_partida = crear_canal(mensaje_invi, jugadores, comienza)
-            .await
-            .map_err(|_| ErrorEmpezarPartida::ErrorCreandoCanal)?;
-        let partida = Arc::new(Mutex::new(partida));
+        let canal_partida =
+            crear_canal(mensaje_invi, jugadores, comienza, invitacion.reglas.parejas)
+                .await
+                .map_err(|_| ErrorEmpezarPartida::ErrorCreandoCanal)?;
         let mut partidas = self.partidas.write().await;
-        partidas.insert(canal_partida, partida.clone());
+        partidas.insert(canal_partida, Arc::new(Mutex::new(partida)));
         Ok(RespuestaEmpezarPartida {
-            invitacion,
             mensaje_invi,
-            partida,
+            canal: canal_partida,
         })
     }
 
+    pub async fn activas(&self) -> Vec<(ChannelId, Arc<Mutex<Partida>>)> {
+        self.partidas
+            .read()
+            .await
+            .iter()
+            .map(|(canal, partida)| (*canal, partida.clone()))
+            .collect()
+    }
+
+    pub async fn agregar_partida(&self, canal: ChannelId, partida: Partida) {
+        self.partidas
+            .write()
+            .await
+            .insert(canal, Arc::new(Mutex::new(partida)));
+    }
+
     pub async fn get_partida(&self, canal: ChannelId) -> Option<Arc<Mutex<Partida>>> {
         self.partidas.read().await.get(&canal).cloned()
     }
 
-    pub async fn terminar_partida(&self, canal: ChannelId) -> Result<()> {
+    pub async fn quitar_inactivas(&self, limite: Duration) -> Vec<(ChannelId, Partida)> {
+        let mut partidas = self.partidas.write().await;
+        let mut inactivas = vec![];
+        for (canal, partida) in partidas.iter() {
+            let partida = partida.lock().await;
+            if partida.tiempo_inactiva() >= limite {
+                inactivas.push((*canal, partida.clone()));
+            }
+        }
+        for (canal, _) in &inactivas {
+            partidas.remove(canal);
+        }
+        inactivas
+    }
+
+    pub fn empezar_turnos_bots(&self, canal: ChannelId) -> bool {
+        self.turnos_bots.lock().unwrap().insert(canal)
+    }
+
+    pub fn terminar_turnos_bots(&self, canal: ChannelId) {
+        self.turnos_bots.lock().unwrap().remove(&canal);
+    }
+
+    pub async fn terminar_partida(&self, canal: ChannelId) -> Result<Partida> {
         let mut partidas = self.partidas.write().await;
         match partidas.remove(&canal) {
-            Some(_) => Ok(()),
+            Some(partida) => Ok(partida.lock().await.clone()),
             _ => Err(anyhow!("La partida no existe")),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Invitacion {
     invitados: Option<HashSet<UserId>>,
     aceptaron: HashSet<UserId>,
     pub max_jugadores: usize,
+    pub reglas: ReglasPartida,
+    pub bots: Vec<Dificultad>,
+    pub pareja: Option<UserId>,
+    #[serde(default = "SystemTime::now")]
+    creada: SystemTime,
 }
 
 impl Invitacion {
-    fn new(invita: UserId, invitados: Option<Vec<UserId>>, max_jugadores: usize) -> Self {
+    pub fn new(
+        invita: UserId,
+        invitados: Option<Vec<UserId>>,
+        max_jugadores: usize,
+        reglas: ReglasPartida,
+        bots: Vec<Dificultad>,
+        pareja: Option<UserId>,
+    ) -> Self {
         Self {
-            invitados: invitados.map(|invs| invs.into_iter().collect()),
+            invitados: invitados.map(|invs| invs.into_iter().chain(pareja).collect()),
             aceptaron: [invita].into_iter().collect(),
             max_jugadores,
+            reglas,
+            bots,
+            pareja,
+            creada: SystemTime::now(),
         }
     }
 
+    pub fn vencida(&self, limite: Duration) -> bool {
+        self.creada.elapsed().unwrap_or(Duration::ZERO) > limite
+    }
+
     pub fn jugadores(&self) -> Vec<UserId> {
         self.aceptaron.iter().cloned().collect()
     }
 
     pub fn llena(&self) -> bool {
-        self.aceptaron.len() >= self.max_jugadores
+        self.aceptaron.len() + self.bots.len() >= self.max_jugadores
     }
 
     pub fn privada(&self) -> bool {
@@ -195,6 +310,14 @@ impl Invitacion {
                 return Err(anyhow!("Perdon sempaii pero no te invitaron :("));
             }
         }
+        if let Some(pareja) = self.pareja {
+            if acepta != pareja
+                && !self.aceptaron.contains(&pareja)
+                && self.aceptaron.len() + self.bots.len() + 2 > self.max_jugadores
+            {
+                return Err(anyhow!("El ultimo lugar esta guardado para la pareja uwu"));
+            }
+        }
         if self.aceptaron.insert(acepta) {
             Ok(())
         } else {
@@ -211,10 +334,48 @@ impl Invitacion {
     }
 }
 
+fn migrar_partidas(version: u32, datos: &[u8]) -> Result<Vec<(ChannelId, Partida)>> {
+    match version {
+        0 => Ok(from_slice(datos)?),
+        _ => Err(anyhow!(
+            "No se como migrar partidas desde la version {}",
+            version
+        )),
+    }
+}
+
+fn migrar_invitaciones(
+    version: u32,
+    datos: &[u8],
+) -> Result<Vec<(ChannelId, UserId, Invitacion, MessageId)>> {
+    match version {
+        0 => Ok(from_slice(datos)?),
+        _ => Err(anyhow!(
+            "No se como migrar invitaciones desde la version {}",
+            version
+        )),
+    }
+}
+
+fn sentar_parejas(
+    creador: UserId,
+    pareja: Option<UserId>,
+    mut jugadores: Vec<UserId>,
+) -> Vec<UserId> {
+    jugadores.shuffle(&mut thread_rng());
+    let pareja = pareja
+        .filter(|p| jugadores.contains(p))
+        .unwrap_or_else(|| *jugadores.iter().find(|j| **j != creador).unwrap());
+    let rivales: Vec<_> = jugadores
+        .into_iter()
+        .filter(|j| *j != creador && *j != pareja)
+        .collect();
+    vec![creador, rivales[0], pareja, rivales[1]]
+}
+
 pub struct RespuestaEmpezarPartida {
-    pub invitacion: Invitacion,
     pub mensaje_invi: MessageId,
-    pub partida: Arc<Mutex<Partida>>,
+    pub canal: ChannelId,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -223,3 +384,36 @@ pub enum ErrorEmpezarPartida {
     PocosJugadores,
     ErrorCreandoCanal,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jugadores() -> Vec<UserId> {
+        (10..14).map(UserId).collect()
+    }
+
+    #[test]
+    fn parejas_se_sientan_alternadas() {
+        for _ in 0..20 {
+            let mesa = sentar_parejas(UserId(10), Some(UserId(12)), jugadores());
+            assert_eq!(mesa[0], UserId(10));
+            assert_eq!(mesa[2], UserId(12));
+            let mut rivales = vec![mesa[1], mesa[3]];
+            rivales.sort();
+            assert_eq!(rivales, vec![UserId(11), UserId(13)]);
+        }
+    }
+
+    #[test]
+    fn pareja_ausente_se_sortea() {
+        for _ in 0..20 {
+            let mesa = sentar_parejas(UserId(10), Some(UserId(99)), jugadores());
+            assert_eq!(mesa[0], UserId(10));
+            assert_ne!(mesa[2], UserId(10));
+            let mut todos = mesa.clone();
+            todos.sort();
+            assert_eq!(todos, jugadores());
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use crate::{
+    chinchon::ReglasPartida,
+    guardado::{escribir_atomico, leer_guardado},
+};
+use anyhow::{anyhow, Result};
+use rand::{prelude::SliceRandom, thread_rng};
+use rmp_serde::from_slice;
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{ChannelId, MessageId, UserId};
+use std::collections::HashMap;
+use tokio::{sync::RwLock, task::spawn_blocking};
+
+#[derive(Default)]
+pub struct ListaTorneos {
+    torneos: RwLock<HashMap<ChannelId, Torneo>>,
+}
+
+impl ListaTorneos {
+    pub async fn cargar() -> Result<Self> {
+        let torneos = spawn_blocking::<_, Result<_>>(|| {
+            let torneos = leer_guardado("torneos", migrar_torneos)?;
+            Ok(torneos.into_iter().collect())
+        })
+        .await
+        .unwrap()?;
+        Ok(Self {
+            torneos: RwLock::new(torneos),
+        })
+    }
+
+    pub async fn guardar(&self) -> Result<()> {
+        let lista: Vec<_> = self
+            .torneos
+            .read()
+            .await
+            .iter()
+            .map(|(canal, torneo)| (*canal, torneo.clone()))
+            .collect();
+        spawn_blocking(move || escribir_atomico("torneos", &lista))
+            .await
+            .unwrap()
+    }
+
+    pub async fn crear(&self, canal: ChannelId, torneo: Torneo) -> Result<()> {
+        let mut torneos = self.torneos.write().await;
+        if torneos.contains_key(&canal) {
+            return Err(anyhow!(
+                "Ya hay un torneo en este canal, esperen a que termine uwu"
+            ));
+        }
+        torneos.insert(canal, torneo);
+        Ok(())
+    }
+
+    pub async fn get(&self, canal: ChannelId) -> Option<Torneo> {
+        self.torneos.read().await.get(&canal).cloned()
+    }
+
+    pub async fn get_por_hilo(&self, hilo: ChannelId) -> Option<ChannelId> {
+        self.torneos
+            .read()
+            .await
+            .iter()
+            .find(|(_, t)| {
+                t.rondas
+                    .last()
+                    .is_some_and(|r| r.iter().any(|m| m.hilo == Some(hilo)))
+            })
+            .map(|(canal, _)| *canal)
+    }
+
+    pub async fn terminar(&self, canal: ChannelId) -> Option<Torneo> {
+        self.torneos.write().await.remove(&canal)
+    }
+
+    pub async fn inscribir(&self, canal: ChannelId, jugador: UserId) -> Result<Torneo> {
+        let mut torneos = self.torneos.write().await;
+        let torneo = torneos
+            .get_mut(&canal)
+            .ok_or_else(|| anyhow!("Este torneo ya no existe :c"))?;
+        if torneo.empezado() {
+            return Err(anyhow!("El torneo ya empezo, llegaste tarde bb"));
+        }
+        if torneo.inscriptos.contains(&jugador) {
+            return Err(anyhow!("Ya estas anotado sempaii"));
+        }
+        torneo.inscriptos.push(jugador);
+        Ok(torneo.clone())
+    }
+
+    pub async fn desinscribir(&self, canal: ChannelId, jugador: UserId) -> Result<Torneo> {
+        let mut torneos = self.torneos.write().await;
+        let torneo = torneos
+            .get_mut(&canal)
+            .ok_or_else(|| anyhow!("Este torneo ya no existe :c"))?;
+        if torneo.empezado() {
+            return Err(anyhow!(
+                "El torneo ya empezo, si no quieres jugar usa **/salir**"
+            ));
+        }
+        let pos = torneo
+            .inscriptos
+            .iter()
+            .position(|j| *j == jugador)
+            .ok_or_else(|| anyhow!("No estabas anotado :/"))?;
+        torneo.inscriptos.remove(pos);
+        Ok(torneo.clone())
+    }
+
+    pub async fn empezar(&self, canal: ChannelId, quien: UserId) -> Result<Torneo> {
+        let mut torneos = self.torneos.write().await;
+        let torneo = torneos
+            .get_mut(&canal)
+            .ok_or_else(|| anyhow!("Este torneo ya no existe :c"))?;
+        if torneo.creador != quien {
+            return Err(anyhow!("Solo quien creo el torneo puede empezarlo"));
+        }
+        if torneo.empezado() {
+            return Err(anyhow!("El torneo ya empezo uwu"));
+        }
+        if torneo.inscriptos.len() < 2 {
+            return Err(anyhow!("Se necesitan al menos 2 jugadores para el torneo"));
+        }
+        let mut jugadores = torneo.inscriptos.clone();
+        jugadores.shuffle(&mut thread_rng());
+        torneo.armar_ronda(jugadores);
+        Ok(torneo.clone())
+    }
+
+    pub async fn reservar_mesas(&self, canal: ChannelId) -> Option<(Torneo, Vec<usize>)> {
+        let mut torneos = self.torneos.write().await;
+        let torneo = torneos.get_mut(&canal)?;
+        let mut reservadas = vec![];
+        for (i, mesa) in torneo.rondas.last_mut()?.iter_mut().enumerate() {
+            if mesa.pendiente() {
+                mesa.creando = true;
+                reservadas.push(i);
+            }
+        }
+        Some((torneo.clone(), reservadas))
+    }
+
+    pub async fn asignar_hilo(&self, canal: ChannelId, mesa: usize, hilo: Option<ChannelId>) {
+        if let Some(mesa) = self
+            .torneos
+            .write()
+            .await
+            .get_mut(&canal)
+            .and_then(|t| t.rondas.last_mut())
+            .and_then(|r| r.get_mut(mesa))
+        {
+            mesa.hilo = hilo;
+            mesa.creando = false;
+        }
+    }
+
+    pub async fn registrar_ganador(
+        &self,
+        hilo: ChannelId,
+        ganador: UserId,
+    ) -> Option<(ChannelId, ResultadoMesa)> {
+        let mut torneos = self.torneos.write().await;
+        let (canal, torneo) = torneos.iter_mut().find(|(_, t)| {
+            t.rondas
+                .last()
+                .is_some_and(|r| r.iter().any(|m| m.hilo == Some(hilo)))
+        })?;
+        let ronda = torneo.rondas.last_mut().unwrap();
+        let mesa = ronda.iter_mut().find(|m| m.hilo == Some(hilo)).unwrap();
+        mesa.ganador = Some(ganador);
+        let ganadores: Option<Vec<_>> = ronda.iter().map(|m| m.ganador).collect();
+        if let Some(campeon) = torneo.campeon() {
+            return Some((*canal, ResultadoMesa::Campeon(campeon)));
+        }
+        match ganadores {
+            Some(ganadores) => {
+                torneo.armar_ronda(ganadores);
+                Some((*canal, ResultadoMesa::NuevaRonda))
+            }
+            None => Some((*canal, ResultadoMesa::Pendiente)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ResultadoMesa {
+    Pendiente,
+    NuevaRonda,
+    Campeon(UserId),
+}
+
+fn migrar_torneos(version: u32, datos: &[u8]) -> Result<Vec<(ChannelId, Torneo)>> {
+    match version {
+        0 => Ok(from_slice(datos)?),
+        _ => Err(anyhow!(
+            "No se como migrar torneos desde la version {}",
+            version
+        )),
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Torneo {
+    pub nombre: String,
+    pub creador: UserId,
+    pub mensaje: MessageId,
+    pub jugadores_por_mesa: usize,
+    pub reglas: ReglasPartida,
+    pub inscriptos: Vec<UserId>,
+    pub rondas: Vec<Vec<Mesa>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Mesa {
+    pub jugadores: Vec<UserId>,
+    pub hilo: Option<ChannelId>,
+    pub ganador: Option<UserId>,
+    #[serde(skip)]
+    creando: bool,
+}
+
+impl Mesa {
+    pub fn pendiente(&self) -> bool {
+        self.hilo.is_none() && self.ganador.is_none() && !self.creando
+    }
+}
+
+impl Torneo {
+    pub fn new(
+        nombre: String,
+        creador: UserId,
+        mensaje: MessageId,
+        jugadores_por_mesa: usize,
+        reglas: ReglasPartida,
+    ) -> Self {
+        Self {
+            nombre,
+            creador,
+            mensaje,
+            jugadores_por_mesa,
+            reglas,
+            inscriptos: vec![],
+            rondas: vec![],
+        }
+    }
+
+    pub fn empezado(&self) -> bool {
+        !self.rondas.is_empty()
+    }
+
+    pub fn mesas_pendientes(&self) -> bool {
+        self.rondas
+            .last()
+            .is_some_and(|r| r.iter().any(|m| m.pendiente()))
+    }
+
+    pub fn campeon(&self) -> Option<UserId> {
+        match self.rondas.last()?.as_slice() {
+            [final_] => final_.ganador,
+            _ => None,
+        }
+    }
+
+    fn armar_ronda(&mut self, jugadores: Vec<UserId>) {
+        let cant_mesas = jugadores.len().div_ceil(self.jugadores_por_mesa);
+        let mut ronda: Vec<_> = (0..cant_mesas)
+            .map(|_| Mesa {
+                jugadores: vec![],
+                hilo: None,
+                ganador: None,
+                creando: false,
+            })
+            .collect();
+        for (i, jugador) in jugadores.into_iter().enumerate() {
+            ronda[i % cant_mesas].jugadores.push(jugador);
+        }
+        for mesa in &mut ronda {
+            if let [solo] = mesa.jugadores[..] {
+                mesa.ganador = Some(solo);
+            }
+        }
+        self.rondas.push(ronda);
+    }
+}
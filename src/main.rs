@@ -1,4 +1,3 @@
-mod chinchon;
 mod comandos;
 mod componentes;
 mod config_servers;
@@ -6,19 +5,25 @@ mod crear_hilo;
 mod errores;
 mod estadisticas;
 mod eventos;
+mod guardado;
 mod handler;
+mod invitaciones_vencidas;
 mod lista_partidas;
+mod lista_torneos;
 mod mensajes;
 mod opciones_comandos;
+mod partidas_inactivas;
+mod reloj_turnos;
 
 use crate::{
-    config_servers::ConfigServers, estadisticas::Estadisticas, handler::Handler,
-    lista_partidas::ListaPartidas,
+    config_servers::ConfigServers, estadisticas::Estadisticas, guardado::guardado_periodico,
+    handler::Handler, invitaciones_vencidas::vencer_invitaciones, lista_partidas::ListaPartidas,
+    partidas_inactivas::cerrar_partidas_inactivas, reloj_turnos::reloj_turnos,
 };
-use chinchon::inicializar_emojis_palos;
+use chinchoncito::chinchon::{self, inicializar_emojis_palos};
 use serde::Deserialize;
 use serenity::{client::Client, model::id::EmojiId, prelude::GatewayIntents};
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tokio::{fs::File, io::AsyncReadExt, signal::ctrl_c, spawn};
 
 #[derive(Deserialize)]
@@ -26,6 +31,18 @@ struct Config {
     token: String,
     redis: String,
     emojis: ConfigEmojis,
+    #[serde(default = "horas_inactividad_default")]
+    horas_inactividad: u64,
+    #[serde(default = "minutos_invitacion_default")]
+    minutos_invitacion: u64,
+}
+
+fn horas_inactividad_default() -> u64 {
+    24
+}
+
+fn minutos_invitacion_default() -> u64 {
+    60
 }
 
 #[derive(Deserialize)]
@@ -61,6 +78,12 @@ async fn main() {
         .await
         .expect("Conectar con redis");
     let partidas = Arc::new(ListaPartidas::cargar().await.expect("Cargar partidas"));
+    let mut estadisticas = Estadisticas::new(con_redis.clone());
+    spawn(async move {
+        if let Err(err) = estadisticas.rellenar_rankings().await {
+            eprintln!("Error rellenando los rankings: {:?}", err);
+        }
+    });
     let handler = {
         let configs = ConfigServers::new(con_redis.clone());
         let estadisticas = Estadisticas::new(con_redis.clone());
@@ -70,6 +93,24 @@ async fn main() {
         .event_handler(handler.clone())
         .await
         .expect("Crear cliente");
+    spawn(reloj_turnos(
+        cliente.cache_and_http.http.clone(),
+        partidas.clone(),
+        ConfigServers::new(con_redis.clone()),
+        Estadisticas::new(con_redis.clone()),
+    ));
+    spawn(cerrar_partidas_inactivas(
+        cliente.cache_and_http.http.clone(),
+        partidas.clone(),
+        Estadisticas::new(con_redis),
+        Duration::from_secs(config.horas_inactividad * 60 * 60),
+    ));
+    spawn(vencer_invitaciones(
+        cliente.cache_and_http.http.clone(),
+        partidas.clone(),
+        Duration::from_secs(config.minutos_invitacion * 60),
+    ));
+    spawn(guardado_periodico(partidas.clone()));
     let shards = cliente.shard_manager.clone();
     spawn(async move {
         ctrl_c().await.expect("Recibir señal Ctrl+C");
@@ -1,16 +1,12 @@
-use super::lista_cartas;
-use crate::chinchon::ResultadoFinalRonda;
-use serenity::{
-    builder::CreateActionRow,
-    http::CacheHttp,
-    model::{id::UserId, user::User},
-};
+use super::{acciones_turno, lista_cartas, mencion, nombre_jugador};
+use crate::chinchon::{es_bot, ResultadoFinalRonda};
+use serenity::{builder::CreateActionRow, http::CacheHttp, model::id::UserId};
 use std::{fmt::Write, iter};
 
 pub async fn mensaje_cortar(
     http: impl CacheHttp,
     resultados: &[ResultadoFinalRonda],
-    corto: &User,
+    corto: &str,
     prox_turno: Option<UserId>,
 ) -> (String, Vec<CreateActionRow>) {
     let chinchon = resultados.iter().find(|r| r.chinchon).cloned();
@@ -18,23 +14,19 @@ pub async fn mensaje_cortar(
         (
             format!(
                 "**{}**-sama hizo chinchon o.O\n{}",
-                corto.name,
+                corto,
                 lista_cartas(&resul_corto.juegos[0])
             ),
             vec![],
         )
     } else {
-        let mut cont = format!("El sempaii **{}** acaba de cortar 😳 😳 😳\n\n", corto.name);
+        let mut cont = format!("El sempaii **{}** acaba de cortar 😳 😳 😳\n\n", corto);
         for res in resultados {
             let _ = write!(
                 cont,
                 "Cartas de **{}**:\n{}\n\
-                Suma {} y se queda en **{}**\n\n",
-                res.jugador
-                    .to_user(&http)
-                    .await
-                    .map(|u| u.name)
-                    .unwrap_or_else(|_| "?".to_owned()),
+                Suma {} y se queda en **{}**\n",
+                nombre_jugador(&http, res.jugador).await,
                 res.juegos
                     .iter()
                     .chain(iter::once(&res.sobrantes).filter(|cs| !cs.is_empty()))
@@ -44,13 +36,46 @@ pub async fn mensaje_cortar(
                 res.puntos_sumados,
                 res.puntos_total
             );
+            if let Some(puntos_pareja) = res.puntos_pareja {
+                let _ = writeln!(cont, "Su pareja tiene **{}**", puntos_pareja);
+            }
+            cont += "\n";
+        }
+        for res in resultados.iter().filter(|r| !r.acomodadas.is_empty()) {
+            let mut acomodadas = res.acomodadas.clone();
+            acomodadas.sort_by_key(|a| (a.jugador, a.juego));
+            for grupo in acomodadas.chunk_by(|a, b| (a.jugador, a.juego) == (b.jugador, b.juego)) {
+                let _ = writeln!(
+                    cont,
+                    "{} acomoda {} en el juego {} de {}",
+                    mencion(res.jugador),
+                    lista_cartas(&grupo.iter().map(|a| a.carta).collect::<Vec<_>>()),
+                    grupo[0].juego + 1,
+                    mencion(grupo[0].jugador)
+                );
+            }
+        }
+        if resultados.iter().any(|r| !r.acomodadas.is_empty()) {
+            cont += "\n";
         }
         let mut acciones = vec![];
         if let Some(turno) = prox_turno {
-            cont += &format!("Ahora es el turno de <@{}> :3", turno);
-            let mut row = CreateActionRow::default();
-            row.create_button(|btn| btn.custom_id(format!("jugar {}", turno)).label("Jugar"));
-            acciones.push(row)
+            let reenganchan: Vec<_> = resultados
+                .iter()
+                .filter(|r| r.puede_reengancharse && !es_bot(r.jugador))
+                .collect();
+            for res in &reenganchan {
+                let _ = writeln!(cont, "{} se puede reenganchar uwu", mencion(res.jugador));
+            }
+            cont += &format!("Ahora es el turno de {} :3", mencion(turno));
+            acciones = acciones_turno(turno);
+            if !reenganchan.is_empty() {
+                if acciones.is_empty() {
+                    acciones.push(CreateActionRow::default());
+                }
+                acciones[0]
+                    .create_button(|btn| btn.custom_id("reenganchar").label("Reengancharse"));
+            }
         }
         (cont, acciones)
     }
@@ -0,0 +1,93 @@
+use super::{nombre_jugador, MAX_LARGO};
+use crate::chinchon::Partida;
+use serenity::http::CacheHttp;
+use std::fmt::Write;
+
+const MAX_RONDAS: usize = 5;
+
+pub async fn mensaje_espectador(http: impl CacheHttp, partida: &Partida) -> String {
+    let reglas = partida.get_reglas();
+    let puntos = partida.get_puntos();
+    let mut cont = format!(
+        "Se juega a **{}**\n\n**Orden de juego:**\n",
+        reglas.limite_puntos
+    );
+    let jugadores = partida.get_jugadores();
+    let turno = partida.get_turno();
+    let inicio = jugadores
+        .iter()
+        .position(|j| *j == turno)
+        .unwrap_or_default();
+    for (i, jugador) in jugadores
+        .iter()
+        .cycle()
+        .skip(inicio)
+        .take(jugadores.len())
+        .enumerate()
+    {
+        let _ = write!(
+            cont,
+            "{}. **{}**: {}",
+            i + 1,
+            nombre_jugador(&http, *jugador).await,
+            puntos[jugador]
+        );
+        if let Some(puntos_pareja) = partida.get_puntos_pareja(*jugador) {
+            let _ = write!(cont, " (pareja {})", puntos_pareja);
+        }
+        if *jugador == turno {
+            cont += " ← juega ahora";
+        } else if !partida.esta_en_juego(*jugador) {
+            cont += " (fuera)";
+        }
+        cont += "\n";
+    }
+    match partida.get_descarte() {
+        Some(descarte) => {
+            let _ = write!(cont, "\n**Descarte:** {}", descarte);
+        }
+        None => cont += "\n**Descarte:** no hay nada",
+    }
+    let _ = write!(
+        cont,
+        "\n**Quedan en el mazo:** {} cartas",
+        partida.get_cartas_mazo()
+    );
+    let rondas = partida.get_rondas();
+    if rondas.is_empty() {
+        return cont;
+    }
+    cont += "\n\n**Rondas anteriores:**";
+    let mut lineas = vec![];
+    let mut largo = cont.chars().count();
+    for (i, ronda) in rondas.iter().enumerate().rev().take(MAX_RONDAS) {
+        let mut linea = format!(
+            "\n{}. {} **{}**:",
+            i + 1,
+            if ronda.chinchon {
+                "Chinchon de"
+            } else {
+                "Corto"
+            },
+            nombre_jugador(&http, ronda.corto).await
+        );
+        for (jugador, sumados, total) in &ronda.puntos {
+            let _ = write!(
+                linea,
+                " {} {:+} ({})",
+                nombre_jugador(&http, *jugador).await,
+                sumados,
+                total
+            );
+        }
+        largo += linea.chars().count();
+        if largo > MAX_LARGO {
+            break;
+        }
+        lineas.push(linea);
+    }
+    for linea in lineas.into_iter().rev() {
+        cont += &linea;
+    }
+    cont
+}
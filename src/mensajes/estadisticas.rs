@@ -4,35 +4,95 @@ use serenity::{
     builder::CreateEmbed,
     model::{id::GuildId, user::User},
 };
+use std::fmt::Write;
 
 pub async fn mensaje_estadisticas(
     jugador: &User,
     server: Option<GuildId>,
     estadisticas: &Estadisticas,
 ) -> Result<CreateEmbed> {
+    let mut embed = CreateEmbed::default();
+    embed.title(format!("Estadisticas de {}", jugador.name));
     let stats_globales = estadisticas.get(jugador.id, None).await?;
-    let mut mensaje = seccion_tabla("Globales", &stats_globales);
+    agregar_secciones(&mut embed, "Globales", &stats_globales);
     if server.is_some() {
         let stats_server = estadisticas.get(jugador.id, server).await?;
-        mensaje = mensaje + "\n\n" + &seccion_tabla("Este servidor", &stats_server);
+        agregar_secciones(&mut embed, "Este servidor", &stats_server);
     }
-    let mut embed = CreateEmbed::default();
-    embed
-        .title(format!("Estadisticas de {}", jugador.name))
-        .description(mensaje);
     Ok(embed)
 }
 
-fn seccion_tabla(nombre: &str, stats: &EstadisticasJugador) -> String {
+fn agregar_secciones(embed: &mut CreateEmbed, nombre: &str, stats: &EstadisticasJugador) {
+    embed.field(format!("{nombre}: partidas"), seccion_partidas(stats), true);
+    embed.field(format!("{nombre}: rondas"), seccion_rondas(stats), true);
+    if let Some(salidas) = seccion_salidas(stats) {
+        embed.field(format!("{nombre}: salidas"), salidas, false);
+    }
+}
+
+fn seccion_partidas(stats: &EstadisticasJugador) -> String {
     let EstadisticasJugador {
         victorias,
-        derrotas,
+        reenganches,
+        victorias_parejas,
+        derrotas_parejas,
+        rating,
+        mejor_racha,
+        ..
+    } = stats;
+    let total = stats.partidas();
+    let mut texto = format!("**Partidas:** {total}\n**Rating:** {rating:.0}");
+    if let Some(porcentaje) = (victorias * 100).checked_div(total) {
+        let _ = write!(texto, "\n**Ganadas:** {victorias} ({porcentaje} %)");
+    }
+    if *mejor_racha > 1 {
+        let _ = write!(texto, "\n**Mejor racha:** {mejor_racha} seguidas");
+    }
+    if *reenganches > 0 {
+        let _ = write!(texto, "\n**Reenganches:** {reenganches}");
+    }
+    let total_parejas = victorias_parejas + derrotas_parejas;
+    if let Some(porcentaje) = (victorias_parejas * 100).checked_div(total_parejas) {
+        let _ = write!(
+            texto,
+            "\n**En parejas:** {total_parejas}, ganadas {victorias_parejas} ({porcentaje} %)"
+        );
+    }
+    texto
+}
+
+fn seccion_rondas(stats: &EstadisticasJugador) -> String {
+    let EstadisticasJugador {
+        rondas,
+        cortes,
+        chinchones,
+        cortes_cero,
+        puntos_sumados,
+        max_puntos_sobrevividos,
+        ..
     } = stats;
-    let total = victorias + derrotas;
-    let mut texto = format!("**{nombre}:**\n**Partidas:** {total}");
-    if total > 0 {
-        let porcentaje = victorias * 100 / total;
-        texto += &format!("\n**Ganadas:** {victorias} ({porcentaje} %)");
+    let mut texto = format!("**Rondas:** {rondas}");
+    if *rondas > 0 {
+        let _ = write!(
+            texto,
+            "\n**Cortes:** {cortes}\n**Cortes sin puntos sueltos:** {cortes_cero}\n**Chinchones:** {chinchones}\
+            \n**Puntos por ronda:** {:.1}\n**Maximo aguantado:** {max_puntos_sobrevividos}",
+            *puntos_sumados as f64 / *rondas as f64
+        );
     }
     texto
 }
+
+fn seccion_salidas(stats: &EstadisticasJugador) -> Option<String> {
+    let EstadisticasJugador {
+        derrotas,
+        abandonos,
+        expulsiones,
+        ..
+    } = stats;
+    (abandonos + expulsiones > 0).then(|| {
+        format!(
+            "**Perdidas:** {derrotas}\n**Abandonos:** {abandonos}\n**Expulsiones:** {expulsiones}"
+        )
+    })
+}
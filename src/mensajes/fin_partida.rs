@@ -1,3 +1,4 @@
+use super::nombre_jugador;
 use rand::{prelude::SliceRandom, thread_rng};
 use serenity::{
     builder::CreateEmbed,
@@ -14,19 +15,31 @@ pub async fn mensaje_fin_partida(
     http: &impl CacheHttp,
     server: GuildId,
     ganador: UserId,
+    pareja: Option<UserId>,
 ) -> CreateEmbed {
-    let nombre = match server.member(http, ganador).await.ok().and_then(|m| m.nick) {
-        Some(nick) => nick,
-        None => ganador
-            .to_user(http)
-            .await
-            .map(|u| u.name)
-            .unwrap_or_else(|_| "?".to_owned()),
-    };
     let mut embed = CreateEmbed::default();
+    match pareja {
+        Some(pareja) => embed
+            .title(format!(
+                "Felicidades {} y {}",
+                apodo(http, server, ganador).await,
+                apodo(http, server, pareja).await
+            ))
+            .description("Ganaron sisi :D"),
+        None => embed
+            .title(format!(
+                "Felicidades {}",
+                apodo(http, server, ganador).await
+            ))
+            .description("Ganaste sisi :D"),
+    };
+    embed.image(IMAGENES.choose(&mut thread_rng()).unwrap());
     embed
-        .title(format!("Felicidades {nombre}"))
-        .description("Ganaste sisi :D")
-        .image(IMAGENES.choose(&mut thread_rng()).unwrap());
-    embed
+}
+
+async fn apodo(http: &impl CacheHttp, server: GuildId, jugador: UserId) -> String {
+    match server.member(http, jugador).await.ok().and_then(|m| m.nick) {
+        Some(nick) => nick,
+        None => nombre_jugador(http, jugador).await,
+    }
 }
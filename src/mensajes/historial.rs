@@ -0,0 +1,86 @@
+use super::nombre_jugador;
+use crate::estadisticas::Estadisticas;
+use anyhow::Result;
+use serenity::{
+    builder::{CreateActionRow, CreateEmbed},
+    http::CacheHttp,
+    model::{application::component::ButtonStyle, user::User},
+};
+use std::{fmt::Write, time::UNIX_EPOCH};
+
+const POR_PAGINA: usize = 5;
+
+pub async fn mensaje_historial(
+    http: &impl CacheHttp,
+    jugador: &User,
+    estadisticas: &Estadisticas,
+    pagina: usize,
+) -> Result<(CreateEmbed, Vec<CreateActionRow>)> {
+    let (partidas, total) = estadisticas
+        .historial(jugador.id, pagina * POR_PAGINA, POR_PAGINA)
+        .await?;
+    let paginas = total.div_ceil(POR_PAGINA).max(1);
+    let mut embed = CreateEmbed::default();
+    embed
+        .title(format!("Partidas de {}", jugador.name))
+        .footer(|f| f.text(format!("Pagina {}/{}", pagina + 1, paginas)));
+    if partidas.is_empty() {
+        embed.description("Todavia no jugo ninguna partida :c");
+    }
+    for partida in partidas {
+        let fecha = partida
+            .fecha
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let puesto = partida
+            .jugadores
+            .iter()
+            .find(|p| p.jugador == jugador.id)
+            .map_or(0, |p| p.puesto);
+        let mut texto = format!(
+            "<t:{fecha}:f> en <#{}>, {} rondas{}",
+            partida.hilo,
+            partida.rondas,
+            if partida.parejas { ", en parejas" } else { "" }
+        );
+        for posicion in &partida.jugadores {
+            let _ = write!(
+                texto,
+                "\n{}. {} ({} puntos)",
+                posicion.puesto,
+                nombre_jugador(http, posicion.jugador).await,
+                posicion.puntos
+            );
+        }
+        let _ = write!(
+            texto,
+            "\n[Ir al hilo](https://discord.com/channels/{}/{})",
+            partida.server, partida.hilo
+        );
+        let titulo = if puesto == 1 {
+            "Ganada 🏆"
+        } else {
+            "Perdida"
+        };
+        embed.field(titulo, texto, false);
+    }
+    let mut row = CreateActionRow::default();
+    row.create_button(|btn| {
+        btn.label("◀")
+            .custom_id(format!(
+                "historial {} {}",
+                jugador.id,
+                pagina.saturating_sub(1)
+            ))
+            .style(ButtonStyle::Secondary)
+            .disabled(pagina == 0)
+    });
+    row.create_button(|btn| {
+        btn.label("▶")
+            .custom_id(format!("historial {} {}", jugador.id, pagina + 1))
+            .style(ButtonStyle::Secondary)
+            .disabled(pagina + 1 >= paginas)
+    });
+    Ok((embed, vec![row]))
+}
@@ -1,22 +1,79 @@
+use crate::{
+    chinchon::{PuntajeParejas, ReglasPartida, TipoMazo},
+    lista_partidas::Invitacion,
+};
 use serenity::{builder::CreateActionRow, http::CacheHttp, model::id::UserId};
 use std::fmt::Write;
 
 pub async fn mensaje_invitacion(
     http: &impl CacheHttp,
     creador: UserId,
-    jugadores: &[UserId],
-    max_jugadores: u64,
-    privada: bool,
+    invitacion: &Invitacion,
 ) -> (String, Vec<CreateActionRow>) {
+    let Invitacion {
+        max_jugadores,
+        reglas,
+        bots,
+        pareja,
+        ..
+    } = invitacion;
+    let jugadores = invitacion.jugadores();
     let mut cont = format!(
         "<@{}> esta buscando alguien pa jugar un chinchocito 😳",
         creador
     );
-    if privada {
+    if invitacion.privada() {
         cont += "\nLa mesa es privada, usa **/invitar** para agregar gente";
     }
-    let mut nombres = Vec::with_capacity(jugadores.len());
-    for j in jugadores {
+    let por_defecto = ReglasPartida::default();
+    if reglas.limite_puntos != por_defecto.limite_puntos
+        || reglas.max_puntos_cortar != por_defecto.max_puntos_cortar
+    {
+        let _ = write!(
+            cont,
+            "\nSe juega a **{}** y se corta con **{}** o menos",
+            reglas.limite_puntos, reglas.max_puntos_cortar
+        );
+    }
+    if reglas.parejas {
+        cont += match reglas.puntaje_parejas {
+            PuntajeParejas::Suma => "\nSe juega en parejas, sumando los puntos de cada una",
+            PuntajeParejas::Maximo => {
+                "\nSe juega en parejas, cada una cuenta los puntos de quien va peor"
+            }
+        };
+        match pareja {
+            Some(pareja) => {
+                let _ = write!(cont, "\n<@{}> hace pareja con <@{}>", creador, pareja);
+            }
+            None => cont += "\nLas parejas se sortean al empezar",
+        }
+    }
+    if let Some(segundos) = reglas.segundos_turno {
+        let _ = write!(
+            cont,
+            "\nCada turno dura **{}** segundos, si no juegas a tiempo se juega solo",
+            segundos
+        );
+    }
+    if reglas.mazo == TipoMazo::De40 {
+        cont += "\nSe juega con el mazo de 40 cartas, sin ochos ni nueves";
+    }
+    if reglas.max_reenganches > 0 {
+        cont += "\nSi te pasas te puedes reenganchar una vez";
+    }
+    if reglas.acomodar {
+        cont += "\nAl cortar se pueden acomodar cartas en los juegos de quien corta";
+    }
+    if reglas.comodines {
+        let _ = write!(
+            cont,
+            "\nSe juega con comodines, si te quedan sueltos suman **{}**",
+            reglas.puntos_comodin
+        );
+    }
+    let mut nombres = Vec::with_capacity(jugadores.len() + bots.len());
+    for j in &jugadores {
         nombres.push(
             j.to_user(http)
                 .await
@@ -24,15 +81,16 @@ pub async fn mensaje_invitacion(
                 .unwrap_or_else(|_| "?".to_owned()),
         );
     }
+    nombres.extend(bots.iter().map(|d| format!("CPU ({})", d.nombre())));
     let _ = write!(
         cont,
         "\n**Jugadores ({}/{}):**\n{}",
-        jugadores.len(),
+        nombres.len(),
         max_jugadores,
         nombres.join(", ")
     );
     let mut acciones = vec![];
-    if (jugadores.len() as u64) < max_jugadores {
+    if !invitacion.llena() {
         let mut row = CreateActionRow::default();
         row.create_button(|btn| btn.label("Unirse").custom_id("aceptar inv"));
         acciones.push(row);
@@ -1,6 +1,6 @@
 use super::lista_cartas;
 use crate::chinchon::{Carta, Jugador};
-use serenity::{builder::CreateActionRow, model::interactions::message_component::ButtonStyle};
+use serenity::{builder::CreateActionRow, model::application::component::ButtonStyle};
 
 pub fn mensaje_jugar(
     jugador: &Jugador<'_>,
@@ -23,10 +23,14 @@ pub fn mensaje_jugar(
                 .options(|opts| {
                     for carta in cartas {
                         opts.create_option(|opt| {
-                            opt.label(carta.num.to_string())
-                                .value(carta.nombre())
-                                .emoji(carta.palo.emoji())
-                                .default_selection(Some(carta) == seleccionada)
+                            opt.label(if carta.es_comodin() {
+                                "Comodin".to_owned()
+                            } else {
+                                carta.num.to_string()
+                            })
+                            .value(carta.nombre())
+                            .emoji(carta.palo.emoji())
+                            .default_selection(Some(carta) == seleccionada)
                         });
                     }
                     opts
@@ -60,7 +64,11 @@ pub fn mensaje_jugar(
         if let Some(carta) = descarte {
             componentes[0].create_button(|btn| {
                 btn.custom_id("levantar descarte")
-                    .label(format!("Llevarse el {}", carta.nombre()))
+                    .label(if carta.es_comodin() {
+                        "Llevarse el comodin".to_owned()
+                    } else {
+                        format!("Llevarse el {}", carta.nombre())
+                    })
             });
         }
         (texto, componentes)
@@ -1,18 +1,34 @@
 mod cortar;
+mod espectador;
 mod estadisticas;
 mod fin_partida;
+mod historial;
 mod invitacion;
 mod jugar;
+mod partida_inactiva;
+mod ranking;
+mod tiro;
+mod torneo;
 mod tus_cartas;
 
 pub use cortar::mensaje_cortar;
+pub use espectador::mensaje_espectador;
 pub use estadisticas::mensaje_estadisticas;
 pub use fin_partida::mensaje_fin_partida;
+pub use historial::mensaje_historial;
 pub use invitacion::mensaje_invitacion;
 pub use jugar::mensaje_jugar;
+pub use partida_inactiva::mensaje_partida_inactiva;
+pub use ranking::mensaje_ranking;
+pub use tiro::mensaje_tiro;
+pub use torneo::mensaje_torneo;
 pub use tus_cartas::mensaje_cartas;
 
-use crate::chinchon::Carta;
+use crate::chinchon::{es_bot, nombre_bot, Carta};
+use serenity::{builder::CreateActionRow, http::CacheHttp, model::id::UserId};
+
+/// Limite de Discord para el contenido de un mensaje
+const MAX_LARGO: usize = 2000;
 
 fn lista_cartas(cartas: &[Carta]) -> String {
     let lista = cartas
@@ -22,3 +38,32 @@ fn lista_cartas(cartas: &[Carta]) -> String {
         .join(" │ ");
     format!("│ {} │", lista)
 }
+
+pub fn mencion(jugador: UserId) -> String {
+    if es_bot(jugador) {
+        format!("**{}**", nombre_bot(jugador))
+    } else {
+        format!("<@{}>", jugador)
+    }
+}
+
+pub async fn nombre_jugador(http: impl CacheHttp, jugador: UserId) -> String {
+    if es_bot(jugador) {
+        return nombre_bot(jugador);
+    }
+    jugador
+        .to_user(http)
+        .await
+        .map(|u| u.name)
+        .unwrap_or_else(|_| "?".to_owned())
+}
+
+pub fn acciones_turno(turno: UserId) -> Vec<CreateActionRow> {
+    let mut acciones = vec![];
+    if !es_bot(turno) {
+        let mut row = CreateActionRow::default();
+        row.create_button(|btn| btn.custom_id(format!("jugar {}", turno)).label("Jugar"));
+        acciones.push(row);
+    }
+    acciones
+}
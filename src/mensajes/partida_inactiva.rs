@@ -0,0 +1,34 @@
+use super::nombre_jugador;
+use crate::chinchon::Partida;
+use serenity::{builder::CreateEmbed, http::CacheHttp};
+
+pub async fn mensaje_partida_inactiva(http: &impl CacheHttp, partida: &Partida) -> CreateEmbed {
+    let puntos = partida.get_puntos();
+    let mut tabla = String::new();
+    let mut puesto = 0;
+    for mut grupo in partida.posiciones() {
+        grupo.sort_by_key(|j| puntos[j]);
+        for jugador in grupo {
+            puesto += 1;
+            tabla += &format!(
+                "{}. **{}** - {} puntos{}\n",
+                puesto,
+                nombre_jugador(http, jugador).await,
+                puntos[&jugador],
+                if partida.esta_en_juego(jugador) {
+                    ""
+                } else {
+                    " (eliminado)"
+                }
+            );
+        }
+    }
+    let mut embed = CreateEmbed::default();
+    embed
+        .title("Se cerro la partida por inactividad u.u")
+        .description(format!(
+            "Nadie jugo en mucho tiempo asi que la termine, asi quedaron:\n{}",
+            tabla
+        ));
+    embed
+}
@@ -0,0 +1,86 @@
+use super::nombre_jugador;
+use crate::estadisticas::{CriterioRanking, Estadisticas, MIN_PARTIDAS_RANKING};
+use anyhow::Result;
+use serenity::{
+    builder::{CreateActionRow, CreateEmbed},
+    http::CacheHttp,
+    model::{application::component::ButtonStyle, id::GuildId},
+};
+use std::fmt::Write;
+
+const POR_PAGINA: usize = 10;
+
+pub async fn mensaje_ranking(
+    http: &impl CacheHttp,
+    server: GuildId,
+    estadisticas: &Estadisticas,
+    criterio: CriterioRanking,
+    pagina: usize,
+) -> Result<(CreateEmbed, Vec<CreateActionRow>)> {
+    let desde = pagina * POR_PAGINA;
+    let (lista, total) = estadisticas
+        .ranking(server, criterio, desde, POR_PAGINA)
+        .await?;
+    let paginas = total.div_ceil(POR_PAGINA).max(1);
+    let mut texto = String::new();
+    for (i, (jugador, valor)) in lista.into_iter().enumerate() {
+        let valor = match criterio {
+            CriterioRanking::Victorias => format!("{valor:.0}"),
+            CriterioRanking::Porcentaje => format!("{valor:.1} %"),
+            CriterioRanking::Rating => format!("{valor:.0}"),
+        };
+        let _ = writeln!(
+            texto,
+            "**{}.** {} — {}",
+            desde + i + 1,
+            nombre_jugador(http, jugador).await,
+            valor
+        );
+    }
+    if texto.is_empty() {
+        texto += "Nadie por aqui todavia, a jugar se ha dicho uwu";
+    }
+    if criterio == CriterioRanking::Porcentaje {
+        let _ = write!(
+            texto,
+            "\nSolo cuentan quienes jugaron al menos {} partidas",
+            MIN_PARTIDAS_RANKING
+        );
+    }
+    let mut embed = CreateEmbed::default();
+    embed
+        .title(format!("Ranking por {}", criterio.nombre().to_lowercase()))
+        .description(texto)
+        .footer(|f| f.text(format!("Pagina {}/{}", pagina + 1, paginas)));
+    let mut paginacion = CreateActionRow::default();
+    paginacion.create_button(|btn| {
+        btn.label("◀")
+            .custom_id(format!(
+                "ranking {} {}",
+                criterio.id(),
+                pagina.saturating_sub(1)
+            ))
+            .style(ButtonStyle::Secondary)
+            .disabled(pagina == 0)
+    });
+    paginacion.create_button(|btn| {
+        btn.label("▶")
+            .custom_id(format!("ranking {} {}", criterio.id(), pagina + 1))
+            .style(ButtonStyle::Secondary)
+            .disabled(pagina + 1 >= paginas)
+    });
+    let mut criterios = CreateActionRow::default();
+    for otro in CriterioRanking::TODOS {
+        criterios.create_button(|btn| {
+            btn.label(otro.nombre())
+                .custom_id(format!("ranking {} 0", otro.id()))
+                .style(if otro == criterio {
+                    ButtonStyle::Primary
+                } else {
+                    ButtonStyle::Secondary
+                })
+                .disabled(otro == criterio)
+        });
+    }
+    Ok((embed, vec![paginacion, criterios]))
+}
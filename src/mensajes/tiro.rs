@@ -0,0 +1,25 @@
+use super::{acciones_turno, mencion};
+use crate::chinchon::{Carta, PilaCartas};
+use serenity::{builder::CreateActionRow, model::id::UserId};
+
+pub fn mensaje_tiro(
+    nombre: &str,
+    pila_levante: Option<PilaCartas>,
+    carta: Carta,
+    turno: UserId,
+) -> (String, Vec<CreateActionRow>) {
+    (
+        format!(
+            "{}-sama {}tiro un {}\n{} te toca uwu",
+            nombre,
+            match pila_levante {
+                Some(PilaCartas::Mazo) => "levanto del mazo y ",
+                Some(PilaCartas::Descartes) => "levanto el descarte y ",
+                None => "",
+            },
+            carta,
+            mencion(turno)
+        ),
+        acciones_turno(turno),
+    )
+}
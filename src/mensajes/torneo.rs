@@ -0,0 +1,104 @@
+use super::{mencion, nombre_jugador, MAX_LARGO};
+use crate::lista_torneos::Torneo;
+use serenity::{
+    builder::CreateActionRow, http::CacheHttp, model::application::component::ButtonStyle,
+};
+use std::fmt::Write;
+
+// Lo que ocupan los avisos de "y N mas" cuando no entra todo
+const MAX_ANOTADOS_OTROS: usize = 16;
+const MAX_MESAS_OTRAS: usize = 24;
+
+pub async fn mensaje_torneo(
+    http: &impl CacheHttp,
+    torneo: &Torneo,
+) -> (String, Vec<CreateActionRow>) {
+    let mut cont = format!(
+        "🏆 **Torneo {}** organizado por <@{}>\nMesas de hasta **{}** jugadores, se juega a **{}**",
+        torneo.nombre, torneo.creador, torneo.jugadores_por_mesa, torneo.reglas.limite_puntos
+    );
+    let campeon = torneo
+        .campeon()
+        .map(|campeon| format!("\n\n👑 Campeon: {}", mencion(campeon)))
+        .unwrap_or_default();
+    let mut largo = cont.chars().count() + campeon.chars().count();
+    let mut nombres = vec![];
+    for (i, j) in torneo.inscriptos.iter().enumerate() {
+        let nombre = nombre_jugador(http, *j).await;
+        if largo + nombre.chars().count() + MAX_ANOTADOS_OTROS > MAX_LARGO / 2 {
+            nombres.push(format!("y {} mas", torneo.inscriptos.len() - i));
+            break;
+        }
+        largo += nombre.chars().count() + 2;
+        nombres.push(nombre);
+    }
+    let _ = write!(
+        cont,
+        "\n**Anotados ({}):**\n{}",
+        torneo.inscriptos.len(),
+        if nombres.is_empty() {
+            "Nadie todavia :c".to_owned()
+        } else {
+            nombres.join(", ")
+        }
+    );
+    let mut largo = cont.chars().count() + campeon.chars().count();
+    let mut rondas = vec![];
+    'rondas: for (i, ronda) in torneo.rondas.iter().enumerate().rev() {
+        let mut texto = format!("\n\n**Ronda {}**", i + 1);
+        for (j, mesa) in ronda.iter().enumerate() {
+            let jugadores = mesa
+                .jugadores
+                .iter()
+                .map(|id| mencion(*id))
+                .collect::<Vec<_>>()
+                .join(" vs ");
+            let estado = match (mesa.ganador, mesa.hilo) {
+                (Some(ganador), _) if mesa.jugadores.len() == 1 => {
+                    format!("{} pasa directo", mencion(ganador))
+                }
+                (Some(ganador), _) => format!("gano {}", mencion(ganador)),
+                (None, Some(hilo)) => format!("jugando en <#{}>", hilo),
+                (None, None) => "esperando".to_owned(),
+            };
+            let linea = format!("\nMesa {}: {} → {}", j + 1, jugadores, estado);
+            if largo + texto.chars().count() + linea.chars().count() + MAX_MESAS_OTRAS > MAX_LARGO {
+                let _ = write!(texto, "\n... y {} mesas mas", ronda.len() - j);
+                rondas.push(texto);
+                break 'rondas;
+            }
+            texto += &linea;
+        }
+        largo += texto.chars().count();
+        rondas.push(texto);
+    }
+    for texto in rondas.into_iter().rev() {
+        cont += &texto;
+    }
+    cont += &campeon;
+    let mut acciones = vec![];
+    if !torneo.empezado() {
+        let mut row = CreateActionRow::default();
+        row.create_button(|btn| btn.label("Anotarse").custom_id("anotarse torneo"));
+        row.create_button(|btn| {
+            btn.label("Bajarse")
+                .custom_id("bajarse torneo")
+                .style(ButtonStyle::Secondary)
+        });
+        row.create_button(|btn| {
+            btn.label("Empezar")
+                .custom_id("empezar torneo")
+                .style(ButtonStyle::Success)
+        });
+        acciones.push(row);
+    } else if torneo.mesas_pendientes() {
+        let mut row = CreateActionRow::default();
+        row.create_button(|btn| {
+            btn.label("Reintentar mesas")
+                .custom_id("reintentar torneo")
+                .style(ButtonStyle::Secondary)
+        });
+        acciones.push(row);
+    }
+    (cont, acciones)
+}
@@ -1,9 +1,9 @@
 use anyhow::{anyhow, Result};
 use serenity::model::{
-    channel::PartialChannel,
-    interactions::application_command::{
-        ApplicationCommandInteraction, ApplicationCommandInteractionDataOptionValue,
+    application::interaction::application_command::{
+        ApplicationCommandInteraction, CommandDataOptionValue,
     },
+    channel::PartialChannel,
     user::User,
 };
 
@@ -12,7 +12,7 @@ pub fn get_opcion_o_none<T>(
     inter: &ApplicationCommandInteraction,
 ) -> Result<Option<T>>
 where
-    ApplicationCommandInteractionDataOptionValue: ValorOpcion<T>,
+    CommandDataOptionValue: ValorOpcion<T>,
 {
     inter
         .data
@@ -35,14 +35,14 @@ pub fn get_opcion_o_default<T>(
     def: T,
 ) -> Result<T>
 where
-    ApplicationCommandInteractionDataOptionValue: ValorOpcion<T>,
+    CommandDataOptionValue: ValorOpcion<T>,
 {
     Ok(get_opcion_o_none(nombre, inter)?.unwrap_or(def))
 }
 
 pub fn get_opcion<T>(nombre: &str, inter: &ApplicationCommandInteraction) -> Result<T>
 where
-    ApplicationCommandInteractionDataOptionValue: ValorOpcion<T>,
+    CommandDataOptionValue: ValorOpcion<T>,
 {
     get_opcion_o_none(nombre, inter)?.ok_or_else(|| anyhow!("opcion {} no encontrada", nombre))
 }
@@ -51,47 +51,47 @@ pub trait ValorOpcion<T> {
     fn valor_opcion(&self) -> Result<T>;
 }
 
-impl ValorOpcion<String> for ApplicationCommandInteractionDataOptionValue {
+impl ValorOpcion<String> for CommandDataOptionValue {
     fn valor_opcion(&self) -> Result<String> {
         match self {
-            ApplicationCommandInteractionDataOptionValue::String(s) => Ok(s.clone()),
+            CommandDataOptionValue::String(s) => Ok(s.clone()),
             _ => Err(anyhow!("tipo invalido")),
         }
     }
 }
 
-impl ValorOpcion<i64> for ApplicationCommandInteractionDataOptionValue {
+impl ValorOpcion<i64> for CommandDataOptionValue {
     fn valor_opcion(&self) -> Result<i64> {
         match self {
-            ApplicationCommandInteractionDataOptionValue::Integer(i) => Ok(*i),
+            CommandDataOptionValue::Integer(i) => Ok(*i),
             _ => Err(anyhow!("tipo invalido")),
         }
     }
 }
 
-impl ValorOpcion<bool> for ApplicationCommandInteractionDataOptionValue {
+impl ValorOpcion<bool> for CommandDataOptionValue {
     fn valor_opcion(&self) -> Result<bool> {
         match self {
-            ApplicationCommandInteractionDataOptionValue::Boolean(b) => Ok(*b),
-            ApplicationCommandInteractionDataOptionValue::String(s) => Ok(s.parse()?),
+            CommandDataOptionValue::Boolean(b) => Ok(*b),
+            CommandDataOptionValue::String(s) => Ok(s.parse()?),
             _ => Err(anyhow!("tipo invalido")),
         }
     }
 }
 
-impl ValorOpcion<User> for ApplicationCommandInteractionDataOptionValue {
+impl ValorOpcion<User> for CommandDataOptionValue {
     fn valor_opcion(&self) -> Result<User> {
         match self {
-            ApplicationCommandInteractionDataOptionValue::User(u, ..) => Ok(u.clone()),
+            CommandDataOptionValue::User(u, ..) => Ok(u.clone()),
             _ => Err(anyhow!("tipo invalido")),
         }
     }
 }
 
-impl ValorOpcion<PartialChannel> for ApplicationCommandInteractionDataOptionValue {
+impl ValorOpcion<PartialChannel> for CommandDataOptionValue {
     fn valor_opcion(&self) -> Result<PartialChannel> {
         match self {
-            ApplicationCommandInteractionDataOptionValue::Channel(c) => Ok(c.clone()),
+            CommandDataOptionValue::Channel(c) => Ok(c.clone()),
             _ => Err(anyhow!("tipo invalido")),
         }
     }
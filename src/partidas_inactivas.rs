@@ -0,0 +1,57 @@
+use crate::{
+    chinchon::es_bot,
+    estadisticas::Estadisticas,
+    eventos::{avanzar_torneo, guardar_resumen},
+    lista_partidas::ListaPartidas,
+    mensajes::{mencion, mensaje_partida_inactiva},
+};
+use serenity::http::Http;
+use std::{sync::Arc, time::Duration};
+use tokio::time::interval;
+
+const INTERVALO_REVISION: Duration = Duration::from_secs(60);
+
+pub async fn cerrar_partidas_inactivas(
+    http: Arc<Http>,
+    partidas: Arc<ListaPartidas>,
+    mut estadisticas: Estadisticas,
+    limite: Duration,
+) {
+    let mut intervalo = interval(INTERVALO_REVISION);
+    loop {
+        intervalo.tick().await;
+        for (canal, partida) in partidas.quitar_inactivas(limite).await {
+            let mensaje = mensaje_partida_inactiva(&http, &partida).await;
+            let _ = canal
+                .send_message(&http, |msg| msg.set_embed(mensaje))
+                .await;
+            let puntos = partida.get_puntos();
+            let lider = partida
+                .posiciones()
+                .first()
+                .and_then(|grupo| grupo.iter().min_by_key(|j| puntos[j]).copied());
+            if let Some(lider) = lider {
+                if partidas.torneos.get_por_hilo(canal).await.is_some() {
+                    let _ = canal
+                        .say(
+                            &http,
+                            format!("En el torneo pasa {} porque iba ganando", mencion(lider)),
+                        )
+                        .await;
+                }
+                avanzar_torneo(&http, &partidas, canal, lider).await;
+            }
+            let _ = canal.edit_thread(&http, |t| t.archived(true)).await;
+            let server = match canal.to_channel(&http).await.ok().and_then(|c| c.guild()) {
+                Some(canal) => canal.guild_id,
+                None => continue,
+            };
+            guardar_resumen(&mut estadisticas, server, canal, &partida).await;
+            for jugador in partida.get_jugadores() {
+                if partida.esta_en_juego(jugador) && !es_bot(jugador) {
+                    let _ = estadisticas.agregar_abandono(server, jugador).await;
+                }
+            }
+        }
+    }
+}
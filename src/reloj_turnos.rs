@@ -0,0 +1,194 @@
+use crate::{
+    chinchon::TurnoVencido,
+    config_servers::ConfigServers,
+    estadisticas::Estadisticas,
+    eventos::{expulsion, fin_partida, jugar_turnos_bots},
+    lista_partidas::ListaPartidas,
+    mensajes::{acciones_turno, mencion, mensaje_tiro, nombre_jugador},
+};
+use serenity::{
+    http::Http,
+    model::id::{ChannelId, GuildId},
+};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::time::interval;
+
+pub async fn reloj_turnos(
+    http: Arc<Http>,
+    partidas: Arc<ListaPartidas>,
+    config_servers: ConfigServers,
+    mut estadisticas: Estadisticas,
+) {
+    let mut intervalo = interval(Duration::from_secs(5));
+    let mut servidores = HashMap::new();
+    loop {
+        intervalo.tick().await;
+        let activas = partidas.activas().await;
+        servidores.retain(|canal, _| activas.iter().any(|(c, _)| c == canal));
+        for (canal, _) in activas {
+            recordar_turno(&http, &partidas, &config_servers, &mut servidores, canal).await;
+            revisar_turno(&http, &partidas, &mut estadisticas, canal).await;
+        }
+    }
+}
+
+async fn recordar_turno(
+    http: &Http,
+    partidas: &ListaPartidas,
+    config_servers: &ConfigServers,
+    servidores: &mut HashMap<ChannelId, GuildId>,
+    canal: ChannelId,
+) {
+    let partida = match partidas.get_partida(canal).await {
+        Some(partida) => partida,
+        None => return,
+    };
+    {
+        let partida = partida.lock().await;
+        let turno = partida.get_turno();
+        if partida.ganador().is_some()
+            || partida.get_bot(turno).is_some()
+            || partida.tiempo_inactiva() < Duration::from_secs(60)
+        {
+            return;
+        }
+    }
+    let server = match servidores.get(&canal) {
+        Some(server) => *server,
+        None => match canal.to_channel(http).await.ok().and_then(|c| c.guild()) {
+            Some(canal_guild) => *servidores.entry(canal).or_insert(canal_guild.guild_id),
+            None => return,
+        },
+    };
+    let recordatorios = match config_servers.get_recordatorios(server).await {
+        Ok(Some(recordatorios)) => recordatorios,
+        _ => return,
+    };
+    let mut partida = partida.lock().await;
+    let turno = partida.get_turno();
+    if partida.ganador().is_some()
+        || partida.get_bot(turno).is_some()
+        || !partida.recordar_turno(recordatorios.intervalo())
+    {
+        return;
+    }
+    drop(partida);
+    let _ = canal
+        .send_message(http, |msg| {
+            msg.content(format!(
+                "{} te estamos esperando, es tu turno uwu",
+                mencion(turno)
+            ))
+            .components(|comps| comps.set_action_rows(acciones_turno(turno)))
+        })
+        .await;
+    if recordatorios.md {
+        if let Ok(md) = turno.create_dm_channel(http).await {
+            let _ = md
+                .say(
+                    http,
+                    format!(
+                        "Te toca jugar en <#{}> sempaii, no nos dejes esperando :c",
+                        canal
+                    ),
+                )
+                .await;
+        }
+    }
+}
+
+async fn revisar_turno(
+    http: &Arc<Http>,
+    partidas: &Arc<ListaPartidas>,
+    estadisticas: &mut Estadisticas,
+    canal: ChannelId,
+) {
+    let partida = match partidas.get_partida(canal).await {
+        Some(partida) => partida,
+        None => return,
+    };
+    let mut partida = partida.lock().await;
+    let limite = match partida.get_reglas().segundos_turno {
+        Some(segundos) => Duration::from_secs(segundos.into()),
+        None => return,
+    };
+    let turno = partida.get_turno();
+    if partida.ganador().is_some() || partida.get_bot(turno).is_some() {
+        return;
+    }
+    let transcurrido = partida.tiempo_inactiva();
+    if transcurrido < limite {
+        if transcurrido >= limite / 2 && partida.avisar_turno() {
+            drop(partida);
+            let _ = canal
+                .say(
+                    http,
+                    format!(
+                        "{} apurate que te quedan {} segundos >:c",
+                        mencion(turno),
+                        (limite - transcurrido).as_secs()
+                    ),
+                )
+                .await;
+        }
+        return;
+    }
+    let pareja = partida.get_pareja(turno);
+    let vencido = partida.jugador(turno).unwrap().jugar_turno_vencido();
+    let prox_turno = partida.get_turno();
+    let ganador = partida.ganador();
+    drop(partida);
+    let server = match canal.to_channel(http).await.ok().and_then(|c| c.guild()) {
+        Some(canal) => canal.guild_id,
+        None => return,
+    };
+    match vencido {
+        TurnoVencido::Jugado { levanto, carta } => {
+            let (mensaje, acciones) = mensaje_tiro(
+                &nombre_jugador(http, turno).await,
+                levanto,
+                carta,
+                prox_turno,
+            );
+            let _ = canal
+                .send_message(http, |msg| {
+                    msg.content(format!(
+                        "Se acabo el tiempo de {} :c\n{}",
+                        mencion(turno),
+                        mensaje
+                    ))
+                    .components(|comps| comps.set_action_rows(acciones))
+                })
+                .await;
+        }
+        TurnoVencido::Expulsado => {
+            let _ = canal
+                .say(
+                    http,
+                    format!(
+                        "{} se quedo sin tiempo demasiadas veces, fuera de la mesa >:c",
+                        mencion(turno)
+                    ),
+                )
+                .await;
+            expulsion(
+                http,
+                estadisticas,
+                server,
+                canal,
+                turno,
+                pareja,
+                ganador.is_none().then_some(prox_turno),
+            )
+            .await;
+        }
+    }
+    match ganador {
+        Some(_) => {
+            if let Ok(partida) = partidas.terminar_partida(canal).await {
+                fin_partida(http, estadisticas, partidas, server, canal, &partida).await;
+            }
+        }
+        None => jugar_turnos_bots(http, estadisticas, partidas, server, canal),
+    }
+}